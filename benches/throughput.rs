@@ -1,9 +1,9 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use miuu_replay::Replay;
+use miuu_replay::{IRewindCurve, Replay, ReplayBuffer};
 use std::hint::black_box;
 
 fn bench_full_replay(file_path: &str, c: &mut Criterion) {
-    let file = std::fs::read(&file_path).unwrap();
+    let file = std::fs::read(file_path).unwrap();
 
     let mut group = c.benchmark_group(format!("throughput/{file_path}"));
     group.throughput(criterion::Throughput::Bytes(file.len() as u64));
@@ -26,10 +26,69 @@ fn bench_full_replay(file_path: &str, c: &mut Criterion) {
     });
 }
 
+/// Sampling the marble's position at 1000 evenly spread times, one by one & through a cursor.
+fn bench_sampling(buffer: &ReplayBuffer, c: &mut Criterion) {
+    let marble = &buffer.rewindables[0];
+    let field = marble.data.iter().find(|d| d.text == "Position").unwrap();
+    let IRewindCurve::Vector3(position) = &field.curve else {
+        panic!("Position isn't a Vector3 curve");
+    };
+    let position = &position.recent_curve;
+    let (start, end) = position.time_range().unwrap();
+    let times = (0..1000)
+        .map(|i| start + (end - start) * i as f32 / 1000.0)
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("sampling/test.replay");
+
+    group.bench_function("sample", |b| {
+        b.iter(|| {
+            for &time in &times {
+                black_box(position.sample(time));
+            }
+        })
+    });
+
+    group.bench_function("cursor", |b| {
+        b.iter(|| {
+            let mut cursor = position.cursor();
+            for &time in &times {
+                black_box(cursor.sample(time));
+            }
+        })
+    });
+}
+
+/// Sharing the identical keyframe timelines of every rewindable again.
+fn bench_timelines(buffer: &ReplayBuffer, c: &mut Criterion) {
+    let mut group = c.benchmark_group("timelines/test.replay");
+
+    group.bench_function("share_timelines", |b| {
+        b.iter_batched_ref(
+            || buffer.clone(),
+            |buffer| {
+                for rewindable in &mut buffer.rewindables {
+                    rewindable.share_timelines();
+                }
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+}
+
 fn bench(c: &mut Criterion) {
-    bench_full_replay("benches/medieval_machinations.replay", c);
+    bench_full_replay("test.replay", c);
+    // bench_full_replay("benches/medieval_machinations.replay", c);
     // bench_full_replay("benches/ribbon.replay", c);
     // bench_full_replay("benches/amethyst.replay", c);
+
+    let file = std::fs::read("test.replay").unwrap();
+    let buffer = Replay::parse(&file)
+        .unwrap()
+        .decode_replay_buffer()
+        .unwrap();
+    bench_sampling(&buffer, c);
+    bench_timelines(&buffer, c);
 }
 
 criterion_group!(throughput, bench);
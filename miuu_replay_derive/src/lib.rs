@@ -0,0 +1,191 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericParam, Ident, LitStr, Token,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+};
+
+/// Derives `ReplayObject` for a wrapper around a `Rewindable`, along with typed field accessors.
+///
+/// The struct needs a single field called `inner` holding the wrapped value,
+/// either generic over `R: Borrow<Rewindable>` or a plain `Rewindable`.
+///
+/// ```ignore
+/// #[derive(ReplayObject)]
+/// #[replay_object(type_name = "GemPickup")]
+/// #[replay_fields(collected: Bool = "Collected", offset: Vector2 = "Offset")]
+/// pub struct Gem<R = Rewindable> {
+///     pub inner: R,
+/// }
+/// ```
+///
+/// Each entry in `replay_fields` becomes an accessor returning the typed curve,
+/// and is listed in `ReplayObject::FIELDS` so `ReplayObject::validate` can check all of them at once.
+#[proc_macro_derive(ReplayObject, attributes(replay_object, replay_fields))]
+pub fn derive_replay_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// `fn_name: RewindType = "Text"`
+struct ReplayField {
+    fn_name: Ident,
+    text: LitStr,
+    rewind_type: Ident,
+}
+
+impl Parse for ReplayField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fn_name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let rewind_type = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let text = input.parse()?;
+
+        Ok(Self {
+            fn_name,
+            text,
+            rewind_type,
+        })
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let krate = quote!(::miuu_replay);
+
+    let mut type_name = None;
+    let mut fields = Vec::new();
+    for attr in &input.attrs {
+        if attr.path().is_ident("replay_object") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type_name") {
+                    type_name = Some(meta.value()?.parse::<LitStr>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `type_name = \"...\"`"))
+                }
+            })?;
+        } else if attr.path().is_ident("replay_fields") {
+            let parsed =
+                attr.parse_args_with(Punctuated::<ReplayField, Token![,]>::parse_terminated)?;
+            fields.extend(parsed);
+        }
+    }
+    let type_name = type_name.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "missing `#[replay_object(type_name = \"...\")]`",
+        )
+    })?;
+
+    let has_inner = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => named
+                .named
+                .iter()
+                .any(|f| f.ident.as_ref().is_some_and(|i| i == "inner")),
+            _ => false,
+        },
+        _ => false,
+    };
+    if !has_inner {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "ReplayObject can only be derived for structs with an `inner` field",
+        ));
+    }
+
+    // the wrapped value is either the one generic type parameter or a plain `Rewindable`
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let inner_type = match generics.params.iter_mut().find_map(|p| match p {
+        GenericParam::Type(t) => Some(t),
+        _ => None,
+    }) {
+        Some(param) => {
+            param.default = None;
+            param
+                .bounds
+                .push(parse_quote!(::std::borrow::Borrow<#krate::Rewindable>));
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        None => quote!(#krate::Rewindable),
+    };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut accessors = Vec::new();
+    let mut schema = Vec::new();
+    for ReplayField {
+        fn_name,
+        text,
+        rewind_type,
+    } in &fields
+    {
+        let return_type = match rewind_type.to_string().as_str() {
+            "Float" => quote!(#krate::RewindCurveFitter<f32>),
+            "Int" => quote!(#krate::RewindCurveFitter<i32>),
+            "Bool" => quote!(#krate::RewindCurveFitter<bool>),
+            "Vector2" => quote!(#krate::RewindCurveFitter<#krate::Vector2>),
+            "Vector3" => quote!(#krate::RewindCurveFitter<#krate::Vector3>),
+            "Quaternion" => quote!(#krate::RewindCurveFitter<#krate::Quaternion>),
+            "UShort" => quote!(#krate::RewindCurveFitter<u16>),
+            "UInt32" => quote!(#krate::RewindCurveFitter<u32>),
+            "UInt32Array" => quote!(#krate::RewindCurveFitterArray<u32>),
+            "Int32Array" => quote!(#krate::RewindCurveFitterArray<i32>),
+            _ => {
+                return Err(Error::new_spanned(
+                    rewind_type,
+                    "expected a `RewindCurveType` variant",
+                ));
+            }
+        };
+        let doc = format!("The `{}` field, a `{rewind_type}` curve.", text.value());
+
+        accessors.push(quote! {
+            #[doc = #doc]
+            pub fn #fn_name(&self) -> ::std::result::Result<&#return_type, #krate::ReplayError> {
+                #krate::__private::find_field(
+                    #krate::ReplayObject::rewindable(self),
+                    #text,
+                    #krate::RewindCurveType::#rewind_type,
+                    |curve| match curve {
+                        #krate::IRewindCurve::#rewind_type(v) => Some(v),
+                        _ => None,
+                    },
+                )
+            }
+        });
+        schema.push(quote! {
+            #krate::ObjectField {
+                name: stringify!(#fn_name),
+                text: #text,
+                rewind_type: #krate::RewindCurveType::#rewind_type,
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics #krate::ReplayObject<#inner_type> for #name #ty_generics #where_clause {
+            const TYPE_NAME: &'static str = #type_name;
+            const FIELDS: &'static [#krate::ObjectField] = &[#(#schema),*];
+
+            fn from_rewindable_unchecked(inner: #inner_type) -> Self {
+                Self { inner }
+            }
+
+            fn rewindable(&self) -> &#krate::Rewindable {
+                ::std::borrow::Borrow::borrow(&self.inner)
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
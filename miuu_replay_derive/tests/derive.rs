@@ -0,0 +1,95 @@
+use miuu_replay::{
+    Replay, ReplayBuffer, ReplayError, ReplayObject, RewindCurveType, Rewindable, Vector2,
+};
+
+const REPLAY_FILE: &[u8] = include_bytes!("../../test.replay");
+
+#[derive(ReplayObject)]
+#[replay_object(type_name = "GemPickup")]
+#[replay_fields(
+    collected: Bool = "Collected",
+    offset: Vector2 = "Offset",
+    spin: UInt32 = "Spin",
+)]
+struct Gem<R = Rewindable> {
+    inner: R,
+}
+
+#[derive(ReplayObject)]
+#[replay_object(type_name = "GemPickup")]
+#[replay_fields(
+    collected: Float = "Collected",
+    missing: Int = "Missing",
+    spin: UInt32 = "Spin",
+)]
+struct BrokenGem {
+    inner: Rewindable,
+}
+
+fn buffer() -> Result<ReplayBuffer, ReplayError> {
+    Replay::parse(REPLAY_FILE)?.decode_replay_buffer()
+}
+
+#[test]
+fn accessors() -> Result<(), ReplayError> {
+    let buffer = buffer()?;
+    let gem = buffer.objects::<Gem<_>>().next().unwrap();
+
+    let collected = gem.collected()?;
+    assert!(!collected.recent_curve.is_empty());
+    let _: Option<Vector2> = gem.offset()?.sample(0.0);
+    let _: Option<u32> = gem.spin()?.sample(0.0);
+    assert!(gem.validate().is_ok());
+
+    assert_eq!(
+        Gem::<Rewindable>::FIELDS
+            .iter()
+            .map(|f| f.name)
+            .collect::<Vec<_>>(),
+        ["collected", "offset", "spin"]
+    );
+    assert_eq!(
+        Gem::<Rewindable>::describe(),
+        "GemPickup { Collected: Bool, Offset: Vector2, Spin: UInt32 }"
+    );
+
+    let owned = Gem::from_rewindable(gem.inner.clone())?;
+    assert_eq!(owned.rewindable().type_name, "GemPickup");
+
+    Ok(())
+}
+
+#[test]
+fn validate_failure() -> Result<(), ReplayError> {
+    let buffer = buffer()?;
+    let gem = BrokenGem::from_rewindable(
+        buffer
+            .generic_objects("GemPickup")
+            .next()
+            .unwrap()
+            .inner
+            .clone(),
+    )?;
+
+    assert!(matches!(
+        gem.collected(),
+        Err(ReplayError::MismatchedCurveTypes {
+            lhs: RewindCurveType::Float,
+            rhs: RewindCurveType::Bool
+        })
+    ));
+    assert!(matches!(gem.missing(), Err(ReplayError::MissingField(_))));
+    assert!(gem.spin().is_ok());
+
+    let Err(ReplayError::InvalidObject { type_name, fields }) = gem.validate() else {
+        panic!("expected an invalid object");
+    };
+    assert_eq!(type_name, "GemPickup");
+    let fields = fields.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        ["Collected: Float is Bool", "Missing: Int is missing"]
+    );
+
+    Ok(())
+}
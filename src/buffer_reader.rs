@@ -0,0 +1,142 @@
+use std::io::Read;
+
+use csharp_binary_encoding::{BinaryReader, DataDecodeError, InvalidDataError};
+
+use crate::{
+    FieldIndex, ParseOptions, ReplayError, ReplayHeader, Rewindable, RewindableData, Vector3,
+    inflate_reader::InflateReader,
+};
+
+/// The header & inflated data of a raw `replay_buffer`, shared by every way of decoding one.
+pub(crate) struct BufferReader<R: Read> {
+    pub(crate) header: ReplayHeader,
+    pub(crate) inflater: InflateReader<R>,
+}
+
+impl<R: Read> BufferReader<R> {
+    /// Reads the 8 byte header, the rest of `reader` is inflated as it's being read.
+    pub(crate) fn new(mut reader: R, options: &ParseOptions) -> Result<Self, ReplayError> {
+        let mut header_reader = BinaryReader::new((&mut reader).take(8));
+        let header = ReplayHeader {
+            session: header_reader.read_i32()?,
+            version: header_reader.read_i32()?,
+        };
+
+        Ok(Self {
+            header,
+            inflater: InflateReader::new(reader, options.max_decompressed_size),
+        })
+    }
+
+    /// Swaps `error` for the inflate error that caused it, if any, while keeping its [`ErrorContext`](crate::ErrorContext).
+    ///
+    /// Errors from inflating only surface as io errors within the reader.
+    pub(crate) fn inflate_error(&mut self, error: ReplayError) -> ReplayError {
+        match self.inflater.take_error() {
+            Some(source) => match error.context() {
+                Some(context) => ReplayError::Context {
+                    context: context.clone(),
+                    source: Box::new(source),
+                },
+                None => source,
+            },
+            None => error,
+        }
+    }
+
+    /// Inflates everything that's left.
+    ///
+    /// On failure everything inflated up until then is returned along with the error.
+    pub(crate) fn read_to_end(mut self) -> (ReplayHeader, Vec<u8>, Option<ReplayError>) {
+        let mut data = Vec::new();
+        let error = match self.inflater.read_to_end(&mut data) {
+            Ok(_) => None,
+            Err(e) => Some(
+                self.inflater
+                    .take_error()
+                    .unwrap_or(ReplayError::Inflate(e)),
+            ),
+        };
+
+        (self.header, data, error)
+    }
+}
+
+/// The part of a [`Rewindable`] before its fields.
+pub(crate) struct RewindableHeader {
+    pub(crate) game_object_name: String,
+    pub(crate) type_name: String,
+    pub(crate) ref_pos: Vector3,
+}
+
+impl RewindableHeader {
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
+    ) -> Result<Self, ReplayError> {
+        let game_object_name = options.read_string(reader)?;
+        let type_name = options.read_string(reader)?;
+
+        let mut read_ref_pos = || -> Result<_, ReplayError> {
+            Ok(Vector3::new((
+                reader.read_f32()?,
+                reader.read_f32()?,
+                reader.read_f32()?,
+            )))
+        };
+        let mut header = Self {
+            game_object_name,
+            type_name,
+            ref_pos: Vector3::new((0.0, 0.0, 0.0)),
+        };
+        header.ref_pos = read_ref_pos().map_err(|e| header.context(e, reader.num_bytes_read()))?;
+
+        Ok(header)
+    }
+
+    /// Attaches the names of the rewindable to an error from reading it.
+    pub(crate) fn context(&self, error: ReplayError, offset: u64) -> ReplayError {
+        error.with_context(offset, |c| {
+            c.game_object_name = Some(self.game_object_name.clone());
+            c.type_name = Some(self.type_name.clone());
+        })
+    }
+
+    /// Builds the [`Rewindable`], indexing its fields.
+    pub(crate) fn into_rewindable(self, data: Vec<RewindableData>) -> Rewindable {
+        Rewindable {
+            game_object_name: self.game_object_name,
+            type_name: self.type_name,
+            ref_pos: self.ref_pos,
+            field_index: FieldIndex::new(&data),
+            data,
+        }
+    }
+}
+
+/// Reads from `data` at `pos` and moves `pos` past everything that was read.
+pub(crate) fn read_at<'a, T>(
+    data: &'a [u8],
+    pos: &mut usize,
+    f: impl FnOnce(&mut BinaryReader<&'a [u8]>) -> Result<T, ReplayError>,
+) -> Result<T, ReplayError> {
+    let mut reader = BinaryReader::new(&data[*pos..]);
+    let value = f(&mut reader).map_err(|e| e.at(reader.num_bytes_read()).offset_by(*pos as u64))?;
+    *pos += reader.num_bytes_read() as usize;
+
+    Ok(value)
+}
+
+/// Moves `pos` forward by `len` bytes without reading them.
+pub(crate) fn skip(data: &[u8], pos: &mut usize, len: usize) -> Result<(), ReplayError> {
+    match pos.checked_add(len) {
+        Some(end) if end <= data.len() => {
+            *pos = end;
+            Ok(())
+        }
+        _ => Err(
+            ReplayError::from(DataDecodeError::from(InvalidDataError::NotEnoughBytes))
+                .at(data.len() as u64),
+        ),
+    }
+}
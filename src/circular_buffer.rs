@@ -1,160 +1,207 @@
+use std::{
+    collections::{VecDeque, vec_deque},
+    ops::{Index, IndexMut},
+};
+
+/// A fixed capacity ring buffer, pushing onto a full buffer overwrites the value at the other end.
+///
+/// Values are always indexed & iterated in chronological order, `0` being the oldest.  
+/// A buffer with a capacity of `0` is always full & empty, pushing onto it drops the value.
 #[derive(Debug, Clone)]
 pub struct CircularBuffer<T: Clone> {
-    buffer: Vec<Option<T>>,
-    start: usize,
-    end: usize,
-    size: usize,
+    buffer: VecDeque<T>,
+    capacity: usize,
 }
 
 impl<T: Clone> CircularBuffer<T> {
+    /// Creates a buffer with a capacity of `0`, see [`with_capacity`](Self::with_capacity).
     pub fn new() -> Self {
-        Self {
-            buffer: Vec::new(),
-            start: 0,
-            end: 0,
-            size: 0,
-        }
+        Self::with_capacity(0)
     }
 
+    /// Creates a buffer that holds at most `capacity` values.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            buffer: vec![None; capacity],
-            start: 0,
-            end: 0,
-            size: 0,
+            buffer: VecDeque::with_capacity(capacity),
+            capacity,
         }
     }
 
+    /// The maximum amount of values.
     pub fn capacity(&self) -> usize {
-        self.buffer.len()
+        self.capacity
     }
 
     pub fn is_full(&self) -> bool {
-        self.size == self.buffer.len()
+        self.buffer.len() == self.capacity
     }
 
     pub fn is_empty(&self) -> bool {
-        self.size == 0
+        self.buffer.is_empty()
     }
 
+    #[deprecated(note = "use `len` instead")]
     pub fn size(&self) -> usize {
-        self.size
+        self.buffer.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len()
     }
 
+    /// Returns the oldest value, panics if the buffer is empty.
     pub fn fast_front(&self) -> &T {
-        &self.buffer[self.start].as_ref().unwrap()
+        self.front().expect("CircularBuffer is empty")
     }
 
+    /// Returns the newest value, panics if the buffer is empty.
     pub fn fast_back(&self) -> &T {
-        &self.buffer[if self.end != 0 {
-            self.end
-        } else {
-            self.capacity() - 1
-        }]
-        .as_ref()
-        .unwrap()
+        self.back().expect("CircularBuffer is empty")
     }
 
-    pub fn get(&self, idx: usize) -> &T {
-        let len = self.buffer.len();
-        let real_idx = self.start
-            + if idx < len - self.start {
-                idx
-            } else {
-                idx - len
-            };
-        &self.buffer[real_idx].as_ref().unwrap()
+    pub fn front(&self) -> Option<&T> {
+        self.buffer.front()
     }
 
-    pub fn set(&mut self, idx: usize, value: T) {
-        let real_idx = self.internal_index(idx);
-        self.buffer[real_idx] = Some(value);
+    pub fn back(&self) -> Option<&T> {
+        self.buffer.back()
     }
 
-    pub fn internal_index(&self, idx: usize) -> usize {
-        let len = self.buffer.len();
-        self.start
-            + if idx < len - self.start {
-                idx
-            } else {
-                idx - len
-            }
+    /// Returns the value at `idx`, panics if it's out of bounds.
+    /// See [`try_get`](Self::try_get) for a non panicking version.
+    pub fn get(&self, idx: usize) -> &T {
+        &self.buffer[idx]
     }
 
-    fn increment(&mut self, idx: usize) -> usize {
-        let mut num = idx + 1;
-        if num == self.capacity() {
-            num = 0;
-        }
-
-        num
+    pub fn try_get(&self, idx: usize) -> Option<&T> {
+        self.buffer.get(idx)
     }
 
-    fn decrement(&mut self, idx: usize) -> usize {
-        let mut num = idx;
-        if num == 0 {
-            num = self.capacity();
-        }
-        num -= 1;
+    pub fn try_get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.buffer.get_mut(idx)
+    }
 
-        num
+    /// Replaces the value at `idx`, panics if it's out of bounds.
+    pub fn set(&mut self, idx: usize, value: T) {
+        self.buffer[idx] = value;
     }
 
+    /// Pushes a value after the newest one, overwriting the oldest value if the buffer is full.
     pub fn push_back(&mut self, value: T) {
-        self.buffer[self.end] = Some(value);
-        self.end = self.increment(self.end);
-
+        if self.capacity == 0 {
+            return;
+        }
         if self.is_full() {
-            self.start = self.end;
-        } else {
-            self.size += 1;
+            self.buffer.pop_front();
         }
+        self.buffer.push_back(value);
     }
 
+    /// Pushes a value before the oldest one, overwriting the newest value if the buffer is full.
     pub fn push_front(&mut self, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
         if self.is_full() {
-            self.start = self.decrement(self.start);
-            self.end = self.start;
-            self.buffer[self.end] = Some(value);
-        } else {
-            self.start = self.decrement(self.start);
-            self.buffer[self.start] = Some(value);
-            self.size += 1;
+            self.buffer.pop_back();
         }
+        self.buffer.push_front(value);
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        self.end = self.decrement(self.end);
-        let value = std::mem::take(&mut self.buffer[self.end]);
-        self.size -= 1;
-
-        value
+        self.buffer.pop_back()
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        let value = std::mem::take(&mut self.buffer[self.start]);
-        self.start = self.increment(self.start);
-        self.size -= 1;
-
-        value
+        self.buffer.pop_front()
     }
 
+    /// Removes all values while keeping the capacity.
     pub fn clear(&mut self) {
-        self.start = 0;
-        self.end = 0;
-        self.size = 0;
         self.buffer.clear();
     }
 
-    pub fn start(&self) -> usize {
-        self.start
+    /// Iterates from the oldest to the newest value.
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.buffer.iter()
+    }
+
+    /// Mutably iterates from the oldest to the newest value.
+    pub fn iter_mut(&mut self) -> vec_deque::IterMut<'_, T> {
+        self.buffer.iter_mut()
+    }
+
+    /// Returns the values as two contiguous slices, which together are in chronological order.
+    /// The second slice is only non empty when the values wrap around the end of the storage.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.buffer.as_slices()
+    }
+
+    /// Rearranges the storage so all values are within one slice & returns it.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.buffer.make_contiguous()
     }
 
-    pub fn end(&self) -> usize {
-        self.end
+    /// Copies all values into a [`Vec`] in chronological order.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.buffer.iter().cloned().collect()
     }
+}
+
+impl<T: Clone> Default for CircularBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Index<usize> for CircularBuffer<T> {
+    type Output = T;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.buffer[idx]
+    }
+}
+
+impl<T: Clone> IndexMut<usize> for CircularBuffer<T> {
+    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+        &mut self.buffer[idx]
+    }
+}
+
+/// Creates a full buffer whose capacity is the amount of values.
+impl<T: Clone> FromIterator<T> for CircularBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let buffer = iter.into_iter().collect::<VecDeque<_>>();
+        Self {
+            capacity: buffer.len(),
+            buffer,
+        }
+    }
+}
+
+impl<T: Clone> IntoIterator for CircularBuffer<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buffer.into_iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a CircularBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buffer.iter()
+    }
+}
+
+impl<'a, T: Clone> IntoIterator for &'a mut CircularBuffer<T> {
+    type Item = &'a mut T;
+    type IntoIter = vec_deque::IterMut<'a, T>;
 
-    pub fn buffer(&self) -> &Vec<Option<T>> {
-        &self.buffer
+    fn into_iter(self) -> Self::IntoIter {
+        self.buffer.iter_mut()
     }
 }
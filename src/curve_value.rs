@@ -0,0 +1,268 @@
+use crate::{
+    IRewindCurve, Quaternion, ReplayError, RewindCurveFitter, RewindCurveFitterArray,
+    RewindCurveType, Vector2, Vector3,
+};
+
+/// A value type that can be stored within a [`RewindCurve`](crate::RewindCurve).
+///
+/// Values are stored back to back as little-endian within the `replay_buffer`,
+/// so each implementation reads & writes its fields explicitly instead of relying on the memory layout.
+pub trait CurveValue: Clone + Sized {
+    /// The amount of bytes a single value takes up within the `replay_buffer`.
+    const SIZE: usize;
+
+    /// The [`RewindCurveType`] of a curve holding this value.
+    const CURVE_TYPE: RewindCurveType;
+
+    /// Returns the inner fitter if `curve` is of [`CURVE_TYPE`](Self::CURVE_TYPE).
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>>;
+
+    /// Decodes a value from exactly [`SIZE`](Self::SIZE) bytes.
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError>;
+
+    /// Encodes the value by appending exactly [`SIZE`](Self::SIZE) bytes to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Blends between two keyframes, where `t` is `0.0` at `from` & `1.0` at `to`.
+    ///
+    /// `t` can be outside of that range when extrapolating.
+    /// Defaults to holding `from`, which is what step values like `bool` & the integers do.
+    fn interpolate(from: &Self, _to: &Self, _t: f32) -> Self {
+        from.clone()
+    }
+}
+
+/// A [`CurveValue`] that the game also stores as an array of curves, only `i32` & `u32`.
+pub trait ArrayCurveValue: CurveValue {
+    /// The [`RewindCurveType`] of an array of curves holding this value.
+    const ARRAY_CURVE_TYPE: RewindCurveType;
+
+    /// Returns the inner array if `curve` is of [`ARRAY_CURVE_TYPE`](Self::ARRAY_CURVE_TYPE).
+    fn fitter_array(curve: &IRewindCurve) -> Option<&RewindCurveFitterArray<Self>>;
+}
+
+fn f32_at(bytes: &[u8], idx: usize) -> f32 {
+    f32::from_le_bytes([
+        bytes[idx * 4],
+        bytes[idx * 4 + 1],
+        bytes[idx * 4 + 2],
+        bytes[idx * 4 + 3],
+    ])
+}
+
+impl CurveValue for f32 {
+    const SIZE: usize = 4;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Float;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Float(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(f32_at(bytes, 0))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn interpolate(from: &Self, to: &Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl CurveValue for i32 {
+    const SIZE: usize = 4;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Int;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Int(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl CurveValue for u32 {
+    const SIZE: usize = 4;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::UInt32;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::UInt32(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl CurveValue for u16 {
+    const SIZE: usize = 2;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::UShort;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::UShort(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl CurveValue for bool {
+    const SIZE: usize = 1;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Bool;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Bool(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        match bytes[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(ReplayError::InvalidBool(value)),
+        }
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl CurveValue for Vector2 {
+    const SIZE: usize = 8;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Vector2;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Vector2(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(Vector2::new((f32_at(bytes, 0), f32_at(bytes, 1))))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+    }
+
+    fn interpolate(from: &Self, to: &Self, t: f32) -> Self {
+        *from + (*to - *from) * t
+    }
+}
+
+impl CurveValue for Vector3 {
+    const SIZE: usize = 12;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Vector3;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Vector3(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(Vector3::new((
+            f32_at(bytes, 0),
+            f32_at(bytes, 1),
+            f32_at(bytes, 2),
+        )))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+        self.z.encode(out);
+    }
+
+    fn interpolate(from: &Self, to: &Self, t: f32) -> Self {
+        *from + (*to - *from) * t
+    }
+}
+
+impl CurveValue for Quaternion {
+    const SIZE: usize = 16;
+    const CURVE_TYPE: RewindCurveType = RewindCurveType::Quaternion;
+
+    fn fitter(curve: &IRewindCurve) -> Option<&RewindCurveFitter<Self>> {
+        match curve {
+            IRewindCurve::Quaternion(fitter) => Some(fitter),
+            _ => None,
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        Ok(Quaternion::new((
+            f32_at(bytes, 0),
+            f32_at(bytes, 1),
+            f32_at(bytes, 2),
+            f32_at(bytes, 3),
+        )))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+        self.z.encode(out);
+        self.w.encode(out);
+    }
+
+    fn interpolate(from: &Self, to: &Self, t: f32) -> Self {
+        from.slerp(to, t)
+    }
+}
+
+impl ArrayCurveValue for i32 {
+    const ARRAY_CURVE_TYPE: RewindCurveType = RewindCurveType::Int32Array;
+
+    fn fitter_array(curve: &IRewindCurve) -> Option<&RewindCurveFitterArray<Self>> {
+        match curve {
+            IRewindCurve::Int32Array(array) => Some(array),
+            _ => None,
+        }
+    }
+}
+
+impl ArrayCurveValue for u32 {
+    const ARRAY_CURVE_TYPE: RewindCurveType = RewindCurveType::UInt32Array;
+
+    fn fitter_array(curve: &IRewindCurve) -> Option<&RewindCurveFitterArray<Self>> {
+        match curve {
+            IRewindCurve::UInt32Array(array) => Some(array),
+            _ => None,
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use std::fmt::Display;
+
 use thiserror::Error;
 
-use crate::{ReplayBuffer, RewindCurveType};
+use crate::{FieldMismatch, Limit, ReplayBuffer, RewindCurveType};
 
 #[derive(Debug, Error)]
 pub enum ReplayError {
@@ -9,17 +11,64 @@ pub enum ReplayError {
         lhs: RewindCurveType,
         rhs: RewindCurveType,
     },
+    #[error(
+        "Unknown curve type: {}{}",
+        .value,
+        .offset.map(|o| format!(" (at byte {o})")).unwrap_or_default()
+    )]
+    UnknownCurveType { value: i32, offset: Option<u64> },
+    #[error("Invalid boolean value: {0}")]
+    InvalidBool(u8),
+    #[error("{limit:?} of {value} is outside the allowed range of 0..={max}")]
+    LimitExceeded {
+        limit: Limit,
+        value: i64,
+        max: usize,
+    },
+    #[error("{frames} frames is more than the allowed {max}")]
+    TooManyFrames { frames: f64, max: usize },
     #[error("Replay is missing a '{}' rewindable", ReplayBuffer::MARBLE_CONTROLLER)]
     NoMarbleController,
+    #[error("Replay has no '{marble}' rewindable named '{0}'", marble = ReplayBuffer::MARBLE_CONTROLLER)]
+    NoMarbleNamed(String),
+    #[error("Replay has multiple '{marble}' rewindables {0:?}, pick one by name", marble = ReplayBuffer::MARBLE_CONTROLLER)]
+    AmbiguousMarble(Vec<String>),
     #[error("{0} field is missing from the rewindable")]
-    MissingField(&'static str),
+    MissingField(String),
+    #[error(
+        "'{type_name}' has mismatched fields: {}",
+        .fields.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    InvalidObject {
+        type_name: &'static str,
+        fields: Vec<FieldMismatch>,
+    },
+    #[error("Expected a '{expected}' rewindable, found '{found}'")]
+    MismatchedObjectType {
+        expected: &'static str,
+        found: String,
+    },
+
+    #[error("{source} ({context})")]
+    Context {
+        context: ErrorContext,
+        source: Box<ReplayError>,
+    },
 
     #[error("{0}")]
     DataDecode(#[from] csharp_binary_encoding::DataDecodeError),
+    /// Any [`std::io::Error`], from reading as well as writing.
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
     #[error("{0}")]
     MessagePack(#[from] rmp_serde::decode::Error),
     #[error("{0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("{0}")]
     Decompress(miniz_oxide::inflate::DecompressError),
+    /// Reading the compressed data failed while it was being inflated.
+    #[error("Failed to read the compressed replay_buffer: {0}")]
+    Inflate(std::io::Error),
 }
 
 impl From<miniz_oxide::inflate::DecompressError> for ReplayError {
@@ -27,3 +76,94 @@ impl From<miniz_oxide::inflate::DecompressError> for ReplayError {
         ReplayError::Decompress(value)
     }
 }
+
+/// Where within the decompressed `replay_buffer` a [`ReplayError`] occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The byte offset within the decompressed `replay_buffer`.
+    pub offset: u64,
+    /// The [`Rewindable::game_object_name`](crate::Rewindable::game_object_name) that was being read.
+    pub game_object_name: Option<String>,
+    /// The [`Rewindable::type_name`](crate::Rewindable::type_name) that was being read.
+    pub type_name: Option<String>,
+    /// The [`RewindableData::text`](crate::RewindableData::text) of the field that was being read.
+    pub field: Option<String>,
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {}", self.offset)?;
+        if let Some(game_object_name) = &self.game_object_name {
+            write!(f, " in '{game_object_name}'")?;
+        }
+        if let Some(type_name) = &self.type_name {
+            write!(f, " ({type_name})")?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " field '{field}'")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ReplayError {
+    /// Returns the [`ErrorContext`] of where the error occurred, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error without any [`ErrorContext`].
+    pub fn root(&self) -> &ReplayError {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+
+    /// Attaches an [`ErrorContext`] at `offset` if the error doesn't have one yet,  
+    /// and then lets `f` fill in the rest of the context.
+    pub(crate) fn with_context(self, offset: u64, f: impl FnOnce(&mut ErrorContext)) -> Self {
+        let (mut context, source) = match self {
+            Self::Context { context, source } => (context, source),
+            err => (
+                ErrorContext {
+                    offset,
+                    ..Default::default()
+                },
+                Box::new(err),
+            ),
+        };
+        f(&mut context);
+
+        Self::Context { context, source }
+    }
+
+    pub(crate) fn at(self, offset: u64) -> Self {
+        self.with_context(offset, |_| {})
+    }
+
+    /// Shifts all offsets by `base`, for errors from a reader that didn't start at the beginning of the buffer.
+    pub(crate) fn offset_by(self, base: u64) -> Self {
+        match self {
+            Self::Context {
+                mut context,
+                source,
+            } => {
+                context.offset += base;
+                Self::Context {
+                    context,
+                    source: Box::new(source.offset_by(base)),
+                }
+            }
+            Self::UnknownCurveType { value, offset } => Self::UnknownCurveType {
+                value,
+                offset: offset.map(|o| o + base),
+            },
+            err => err,
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    CurveValue, Quaternion, ReplayError, RewindCurveFitter, Rewindable, RewindableData, Vector2,
+    Vector3,
+    rewind_curve::{IRewindCurve, merge_ranges},
+};
+
+/// Curves resampled onto one shared fixed-rate timeline, stored column by column.
+///
+/// Every column has exactly one value per entry in [`times`](Self::times).
+/// Values are sampled the same way as [`RewindCurveFitter::sample`], so they're clamped before & after each curve.  
+/// Curves without any keyframes have nothing to sample, so they don't get a column.
+#[derive(Debug, Clone, Default)]
+pub struct FrameTable {
+    pub times: Vec<f32>,
+    pub columns: Vec<Column>,
+}
+
+/// A single named column within a [`FrameTable`]
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    pub values: ColumnValues,
+}
+
+/// The values of a [`Column`], one variant per curve value type.
+#[derive(Debug, Clone)]
+pub enum ColumnValues {
+    Float(Vec<f32>),
+    Int(Vec<i32>),
+    Bool(Vec<bool>),
+    Vector2(Vec<Vector2>),
+    Vector3(Vec<Vector3>),
+    Quaternion(Vec<Quaternion>),
+    UShort(Vec<u16>),
+    UInt32(Vec<u32>),
+}
+
+impl ColumnValues {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Float(values) => values.len(),
+            Self::Int(values) => values.len(),
+            Self::Bool(values) => values.len(),
+            Self::Vector2(values) => values.len(),
+            Self::Vector3(values) => values.len(),
+            Self::Quaternion(values) => values.len(),
+            Self::UShort(values) => values.len(),
+            Self::UInt32(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl FrameTable {
+    /// The most frames a timeline may have, about 77 hours at 60 frames per second.
+    pub const MAX_FRAMES: usize = 1 << 24;
+
+    /// Creates a table without any columns,
+    /// whose timeline goes from `start` up to & including `end` at `rate` frames per second.
+    ///
+    /// A `rate` that isn't positive or an `end` before `start` gives an empty timeline,
+    /// more than [`MAX_FRAMES`](Self::MAX_FRAMES) frames fail with [`ReplayError::TooManyFrames`].
+    pub fn with_timeline(start: f32, end: f32, rate: f32) -> Result<Self, ReplayError> {
+        let interval = 1.0 / rate;
+        let frames = if interval.is_finite() && interval > 0.0 && end >= start {
+            ((end as f64 - start as f64) * rate as f64).floor() + 1.0
+        } else {
+            0.0
+        };
+
+        if frames.is_nan() || frames > Self::MAX_FRAMES as f64 {
+            return Err(ReplayError::TooManyFrames {
+                frames,
+                max: Self::MAX_FRAMES,
+            });
+        }
+
+        Ok(Self {
+            times: (0..frames as usize)
+                .map(|i| start + interval * i as f32)
+                .collect(),
+            columns: Vec::new(),
+        })
+    }
+
+    /// Resamples `fields` onto a timeline that covers all of them, see [`add_field`](Self::add_field).
+    ///
+    /// Fields whose curves have no keyframes are left out of the table.
+    pub fn from_fields<'a>(
+        fields: impl IntoIterator<Item = &'a RewindableData>,
+        rate: f32,
+    ) -> Result<Self, ReplayError> {
+        let fields = fields.into_iter().collect::<Vec<_>>();
+        let (start, end) =
+            merge_ranges(fields.iter().map(|f| f.curve.time_range())).unwrap_or((0.0, 0.0));
+
+        let mut table = Self::with_timeline(start, end, rate)?;
+        for field in fields {
+            table.add_field(field.text.clone(), field);
+        }
+
+        Ok(table)
+    }
+
+    /// Resamples every field of a [`Rewindable`], see [`from_fields`](Self::from_fields).
+    pub fn from_rewindable(rewindable: &Rewindable, rate: f32) -> Result<Self, ReplayError> {
+        Self::from_fields(&rewindable.data, rate)
+    }
+
+    /// Resamples `field` onto the timeline & adds it as a column called `name`.
+    ///
+    /// Arrays are expanded into one column per curve, called `name[0]`, `name[1]` and so on.  
+    /// Curves without any keyframes are left out, since there's nothing to sample.
+    pub fn add_field(&mut self, name: impl Into<String>, field: &RewindableData) {
+        let name = name.into();
+        match &field.curve {
+            IRewindCurve::Float(fitter) => self.push(name, fitter, ColumnValues::Float),
+            IRewindCurve::Int(fitter) => self.push(name, fitter, ColumnValues::Int),
+            IRewindCurve::Bool(fitter) => self.push(name, fitter, ColumnValues::Bool),
+            IRewindCurve::Vector2(fitter) => self.push(name, fitter, ColumnValues::Vector2),
+            IRewindCurve::Vector3(fitter) => self.push(name, fitter, ColumnValues::Vector3),
+            IRewindCurve::Quaternion(fitter) => self.push(name, fitter, ColumnValues::Quaternion),
+            IRewindCurve::UShort(fitter) => self.push(name, fitter, ColumnValues::UShort),
+            IRewindCurve::UInt32(fitter) => self.push(name, fitter, ColumnValues::UInt32),
+            IRewindCurve::UInt32Array(array) => {
+                for (i, fitter) in array.curves.iter().enumerate() {
+                    self.push(format!("{name}[{i}]"), fitter, ColumnValues::UInt32);
+                }
+            }
+            IRewindCurve::Int32Array(array) => {
+                for (i, fitter) in array.curves.iter().enumerate() {
+                    self.push(format!("{name}[{i}]"), fitter, ColumnValues::Int);
+                }
+            }
+        }
+    }
+
+    /// Finds the column called `name`.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// The amount of frames within the table.
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    fn push<T: CurveValue>(
+        &mut self,
+        name: String,
+        fitter: &RewindCurveFitter<T>,
+        column: impl FnOnce(Vec<T>) -> ColumnValues,
+    ) {
+        let mut cursor = fitter.cursor();
+        let values = self
+            .times
+            .iter()
+            .map(|&time| cursor.sample(time))
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(values) = values {
+            self.columns.push(Column {
+                name,
+                values: column(values),
+            });
+        }
+    }
+}
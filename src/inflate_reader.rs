@@ -0,0 +1,124 @@
+use std::io::{self, Read};
+
+use miniz_oxide::{
+    MZError, MZFlush, MZStatus,
+    inflate::{
+        DecompressError, TINFLStatus,
+        stream::{InflateState, inflate},
+    },
+};
+
+use crate::{ReplayError, parse_options::Limit};
+
+/// Inflates raw DEFLATE data from `inner` as it's being read, without buffering the whole output.
+///
+/// Any failure is stored as a [`ReplayError`] which can be retrieved with [`take_error`](Self::take_error),
+/// since the [`Read`] trait can only return an [`io::Error`].
+pub(crate) struct InflateReader<R: Read> {
+    inner: R,
+    state: Box<InflateState>,
+    input: Box<[u8]>,
+    input_pos: usize,
+    input_len: usize,
+    input_done: bool,
+    stream_done: bool,
+    total_out: usize,
+    max_out: usize,
+    error: Option<ReplayError>,
+}
+
+impl<R: Read> InflateReader<R> {
+    const INPUT_SIZE: usize = 32 * 1024;
+
+    pub(crate) fn new(inner: R, max_out: usize) -> Self {
+        Self {
+            inner,
+            state: InflateState::new_boxed(miniz_oxide::DataFormat::Raw),
+            input: vec![0; Self::INPUT_SIZE].into_boxed_slice(),
+            input_pos: 0,
+            input_len: 0,
+            input_done: false,
+            stream_done: false,
+            total_out: 0,
+            max_out,
+            error: None,
+        }
+    }
+
+    /// Takes the error that made the last [`read`](Read::read) fail, if any.
+    pub(crate) fn take_error(&mut self) -> Option<ReplayError> {
+        self.error.take()
+    }
+
+    fn fail(&mut self, error: ReplayError) -> io::Error {
+        let io_error = io::Error::other(error.to_string());
+        self.error = Some(error);
+        io_error
+    }
+
+    fn decompress_error(&mut self, status: TINFLStatus) -> io::Error {
+        self.fail(ReplayError::Decompress(DecompressError {
+            status,
+            output: Vec::new(),
+        }))
+    }
+}
+
+impl<R: Read> Read for InflateReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.stream_done {
+            return Ok(0);
+        }
+
+        loop {
+            if self.input_pos == self.input_len && !self.input_done {
+                self.input_len = match self.inner.read(&mut self.input) {
+                    Ok(len) => len,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => return Err(e),
+                    Err(e) => return Err(self.fail(ReplayError::Inflate(e))),
+                };
+                self.input_pos = 0;
+                self.input_done = self.input_len == 0;
+            }
+
+            // allow one byte past the limit so that hitting it exactly isn't an error
+            let out_len = buf.len().min(
+                self.max_out
+                    .saturating_sub(self.total_out)
+                    .saturating_add(1),
+            );
+            let result = inflate(
+                &mut self.state,
+                &self.input[self.input_pos..self.input_len],
+                &mut buf[..out_len],
+                MZFlush::None,
+            );
+            self.input_pos += result.bytes_consumed;
+            self.total_out += result.bytes_written;
+
+            if self.total_out > self.max_out {
+                return Err(self.fail(ReplayError::LimitExceeded {
+                    limit: Limit::DecompressedSize,
+                    value: self.total_out as i64,
+                    max: self.max_out,
+                }));
+            }
+
+            match result.status {
+                Ok(MZStatus::StreamEnd) => {
+                    self.stream_done = true;
+                    return Ok(result.bytes_written);
+                }
+                Ok(_) | Err(MZError::Buf) if result.bytes_written > 0 => {
+                    return Ok(result.bytes_written);
+                }
+                Ok(_) => {}
+                Err(MZError::Buf) if !self.input_done => {}
+                Err(MZError::Buf) => {
+                    return Err(self.decompress_error(TINFLStatus::FailedCannotMakeProgress));
+                }
+                Err(_) => return Err(self.decompress_error(self.state.last_status())),
+            }
+        }
+    }
+}
@@ -1,34 +1,64 @@
 #![doc = include_str!("../readme.md")]
 
+// lets `derive(ReplayObject)` refer to `::miuu_replay` from within this crate too
+extern crate self as miuu_replay;
+
+mod buffer_reader;
 mod circular_buffer;
+mod curve_value;
 mod error;
+mod frame_table;
+mod inflate_reader;
 mod objects;
+mod parse_options;
 mod quaternion;
 mod replay;
 mod replay_buffer;
+mod replay_buffer_index;
+mod replay_player;
+mod replay_ref;
 mod rewind_curve;
 mod rewind_curve_fitter;
 mod rewind_curve_type;
 mod rewindable;
+mod salvage;
+mod schema;
 mod vector2;
 mod vector3;
 
 pub use circular_buffer::CircularBuffer;
-pub use error::ReplayError;
-pub use objects::{bumper::Bumper, elevator::Elevator, marble::Marble, powerup::Powerup};
+pub use curve_value::{ArrayCurveValue, CurveValue};
+pub use error::{ErrorContext, ReplayError};
+pub use frame_table::{Column, ColumnValues, FrameTable};
+pub use miuu_replay_derive::ReplayObject;
+#[doc(hidden)]
+pub use objects::__private;
+pub use objects::{
+    FieldMismatch, ObjectField, ReplayObject, bumper::Bumper, checkpoint::Checkpoint,
+    elevator::Elevator, gem::Gem, generic::GenericObject, marble::Marble, powerup::Powerup,
+};
+pub use parse_options::{Limit, ParseOptions};
 pub use quaternion::Quaternion;
 pub use replay::*;
-pub use replay_buffer::{ReplayBuffer, ReplayHeader};
-pub use rewind_curve::RewindCurve;
+pub use replay_buffer::{ReplayBuffer, ReplayHeader, ReplayObjects};
+pub use replay_buffer_index::{FieldEntry, ReplayBufferIndex, RewindableEntry};
+pub use replay_player::{
+    BumperState, ElevatorState, Frames, MarbleState, PowerupState, ReplayPlayer, Snapshot,
+};
+pub use replay_ref::{ReplayCosmeticsRef, ReplayDataRef, ReplayRef};
+pub use rewind_curve::{CurveCursor, Extrapolation, IRewindCurve, RewindCurve};
 pub use rewind_curve_fitter::{RewindCurveFitter, RewindCurveFitterArray};
 pub use rewind_curve_type::RewindCurveType;
-pub use rewindable::{Rewindable, RewindableData};
+pub use rewindable::{FieldIndex, Rewindable, RewindableData};
+pub use salvage::{Diagnostic, SalvagedReplayBuffer};
+pub use schema::{FieldSchema, FieldVariant, ReplaySchema, SampleCounts, SchemaChange, TypeSchema};
 pub use vector2::Vector2;
 pub use vector3::Vector3;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Read;
 
     const REPLAY_FILE: &[u8] = include_bytes!("../test.replay");
 
@@ -41,4 +71,831 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn round_trip() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        assert_eq!(replay.to_bytes()?, REPLAY_FILE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn encode_round_trip() -> Result<(), ReplayError> {
+        let mut replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+
+        replay.encode_replay_buffer(&buffer)?;
+        let decoded = replay.decode_replay_buffer()?;
+        assert_eq!(decoded.rewindable_count, buffer.rewindable_count);
+        assert_eq!(decoded.rewindables.len(), buffer.rewindables.len());
+
+        // the vector types can't be compared directly, so values are compared by their raw bytes
+        fn raw<T: CurveValue>(values: &[T]) -> Vec<u8> {
+            let mut out = Vec::new();
+            values.iter().for_each(|value| value.encode(&mut out));
+            out
+        }
+        fn keyframes<T: CurveValue>(fitter: &RewindCurveFitter<T>) -> (bool, Vec<f32>, Vec<u8>) {
+            let curve = &fitter.recent_curve;
+            (
+                fitter.interpolated,
+                curve.times().to_vec(),
+                raw(curve.values()),
+            )
+        }
+        fn curves(curve: &IRewindCurve) -> Vec<(bool, Vec<f32>, Vec<u8>)> {
+            match curve {
+                IRewindCurve::Float(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::Int(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::Bool(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::Vector2(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::Vector3(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::Quaternion(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::UShort(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::UInt32(fitter) => vec![keyframes(fitter)],
+                IRewindCurve::UInt32Array(array) => array.curves.iter().map(keyframes).collect(),
+                IRewindCurve::Int32Array(array) => array.curves.iter().map(keyframes).collect(),
+            }
+        }
+
+        for (lhs, rhs) in decoded.rewindables.iter().zip(&buffer.rewindables) {
+            assert_eq!(lhs.game_object_name, rhs.game_object_name);
+            assert_eq!(lhs.type_name, rhs.type_name);
+            assert_eq!(raw(&[lhs.ref_pos]), raw(&[rhs.ref_pos]));
+            assert_eq!(lhs.data.len(), rhs.data.len());
+
+            for (lhs, rhs) in lhs.data.iter().zip(&rhs.data) {
+                assert_eq!(lhs.index, rhs.index);
+                assert_eq!(lhs.text, rhs.text);
+                assert_eq!(lhs.rewind_type, rhs.rewind_type);
+                assert_eq!(lhs.curve.curve_type(), rhs.curve.curve_type());
+                assert_eq!(curves(&lhs.curve), curves(&rhs.curve));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_curve_type() {
+        assert_eq!(
+            RewindCurveType::try_from(4).ok(),
+            Some(RewindCurveType::Vector3)
+        );
+        assert!(matches!(
+            RewindCurveType::try_from(42),
+            Err(ReplayError::UnknownCurveType { value: 42, .. })
+        ));
+    }
+
+    #[test]
+    fn curve_values() -> Result<(), ReplayError> {
+        let mut raw = Vec::new();
+        Vector3::new((1.0, -2.5, 3.25)).encode(&mut raw);
+        assert_eq!(raw.len(), Vector3::SIZE);
+        assert_eq!(Vector3::decode(&raw)?, Vector3::new((1.0, -2.5, 3.25)));
+
+        assert!(bool::decode(&[1])?);
+        assert!(matches!(
+            bool::decode(&[2]),
+            Err(ReplayError::InvalidBool(2))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sampling() -> Result<(), ReplayError> {
+        let mut curve = RewindCurve::new(true, vec![0.0, 1.0, 2.0], vec![0.0, 10.0, 30.0]);
+
+        assert_eq!(curve.sample(0.5), Some(5.0));
+        assert_eq!(curve.sample(1.5), Some(20.0));
+        assert_eq!(curve.sample(2.0), Some(30.0));
+        assert_eq!(curve.sample(-1.0), Some(0.0));
+        assert_eq!(curve.sample_with(-1.0, Extrapolation::Linear), Some(-10.0));
+        assert_eq!(curve.sample_with(3.0, Extrapolation::Linear), Some(50.0));
+        assert_eq!(curve.sample_with(3.0, Extrapolation::Discard), None);
+
+        curve.interpolated = false;
+        assert_eq!(curve.sample(1.5), Some(10.0));
+        assert_eq!(curve.sample_with(3.0, Extrapolation::Linear), Some(30.0));
+
+        let from = Quaternion::new((0.0, 0.0, 0.0, 1.0));
+        let to = Quaternion::new((0.0, 1.0, 0.0, 0.0));
+        let half = Quaternion::interpolate(&from, &to, 0.5);
+        assert!((half.y - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((half.magnitude() - 1.0).abs() < 1e-6);
+
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+        let marble = buffer.get_marble()?;
+        let position = marble.position()?;
+        let (start, end) = position.recent_curve.time_range().unwrap();
+        assert_eq!(
+            position.sample(start),
+            Some(position.recent_curve.values()[0])
+        );
+        assert!(position.sample((start + end) / 2.0).is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cursor() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+        let marble = buffer.get_marble()?;
+        let position = marble.position()?;
+
+        let (start, end) = position.recent_curve.time_range().unwrap();
+        let frames = (0..=240).map(|i| start - 0.5 + (end - start + 1.0) * i as f32 / 240.0);
+
+        let mut cursor = position.cursor();
+        for time in frames
+            .clone()
+            .chain(frames.rev())
+            .chain([end, start, end / 2.0])
+        {
+            assert_eq!(cursor.sample(time), position.sample(time));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn player() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+        let mut player = ReplayPlayer::new(&buffer);
+        assert!(player.start_time() < player.end_time());
+
+        let end = player.end_time();
+        let snapshot = player.seek(end);
+        assert_eq!(snapshot.marbles.len(), 2);
+        let marble = &snapshot.marbles[0];
+        assert_eq!(marble.game_object_name, "Marble");
+        assert_eq!(snapshot.marbles[1].game_object_name, "Ghost");
+        assert_eq!(snapshot.powerups.len(), 2);
+        assert_eq!(snapshot.bumpers.len(), 1);
+        assert_eq!(snapshot.elevators.len(), 1);
+
+        let mut other = buffer.clone();
+        let position = other.get_marble()?.position()?.sample(end);
+        assert_eq!(marble.position, position);
+        let ghost = other.marble_named("Ghost")?.position()?.sample(end);
+        assert_eq!(snapshot.marbles[1].position, ghost);
+
+        let snapshot = player.step(-0.5);
+        assert_eq!(snapshot.time, end - 0.5);
+
+        player.seek(player.start_time());
+        let frames = player.frames(60.0).count();
+        let expected = ((end - player.start_time()) * 60.0) as usize + 1;
+        assert!(frames.abs_diff(expected) <= 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn frame_table() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+        let marble = buffer.get_marble()?;
+
+        let table = FrameTable::from_rewindable(&marble, 60.0)?;
+        assert!(!table.is_empty());
+        assert!(table.columns.iter().all(|c| c.values.len() == table.len()));
+
+        let Some(ColumnValues::Vector3(positions)) = table.column("Position").map(|c| &c.values)
+        else {
+            panic!("missing Position column");
+        };
+        let position = marble.position()?;
+        assert_eq!(Some(positions[1]), position.sample(table.times[1]));
+        assert!(table.column("EffectState[0]").is_some());
+
+        let table =
+            FrameTable::from_fields(marble.data.iter().filter(|d| d.text == "Omega"), 30.0)?;
+        assert_eq!(table.columns.len(), 1);
+
+        assert!(FrameTable::with_timeline(0.0, 1.0, 0.0)?.is_empty());
+        assert_eq!(FrameTable::with_timeline(0.0, 1.0, 4.0)?.len(), 5);
+        assert!(matches!(
+            FrameTable::with_timeline(0.0, f32::MAX, 60.0),
+            Err(ReplayError::TooManyFrames { .. })
+        ));
+        assert!(matches!(
+            FrameTable::with_timeline(0.0, 1.0, f32::MIN_POSITIVE),
+            Ok(table) if table.len() == 1
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn circular_buffer() -> Result<(), ReplayError> {
+        let mut buffer = CircularBuffer::with_capacity(3);
+        for i in 0..5 {
+            buffer.push_back(i);
+        }
+
+        assert_eq!(buffer.to_vec(), [2, 3, 4]);
+        assert_eq!(buffer[0], 2);
+        assert_eq!(buffer.try_get(3), None);
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2]);
+
+        buffer.push_front(1);
+        assert_eq!((buffer.front(), buffer.back()), (Some(&1), Some(&3)));
+
+        let (a, b) = buffer.as_slices();
+        assert_eq!([a, b].concat(), [1, 2, 3]);
+        assert_eq!(buffer.make_contiguous(), [1, 2, 3]);
+
+        let mut empty = CircularBuffer::new();
+        empty.push_back(1);
+        empty.push_front(2);
+        assert!(empty.is_empty() && empty.is_full());
+        assert_eq!(CircularBuffer::<i32>::default().capacity(), 0);
+
+        let collected = (0..3).collect::<CircularBuffer<_>>();
+        assert!(collected.is_full());
+        assert_eq!(collected.capacity(), 3);
+        let collected = std::iter::empty().collect::<CircularBuffer<i32>>();
+        assert_eq!(collected.capacity(), 0);
+
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut replay_buffer = replay.decode_replay_buffer()?;
+        let marble = replay_buffer.get_marble()?;
+        let curve = &marble.position()?.recent_curve;
+        assert_eq!(curve.times().len(), curve.values().len());
+        assert_eq!(curve.iter().count(), curve.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn shared_timelines() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+        let marble = buffer.get_marble()?;
+
+        let position = &marble.position()?.recent_curve;
+        let velocity = &marble.velocity()?.recent_curve;
+        assert!(position.shares_timeline(velocity));
+        assert!(position.shares_timeline(&marble.qw()?.recent_curve));
+
+        let ghost = buffer
+            .rewindables
+            .iter()
+            .find(|r| r.game_object_name == "Ghost")
+            .unwrap();
+        let ghost_position = ghost.data.iter().find(|d| d.text == "Position").unwrap();
+        let IRewindCurve::Vector3(ghost_position) = &ghost_position.curve else {
+            panic!("Position isn't a Vector3 curve");
+        };
+        assert!(!position.shares_timeline(&ghost_position.recent_curve));
+
+        let mut rewindable = Rewindable {
+            data: vec![marble.data[0].clone(), marble.data[0].clone()],
+            ..marble.inner.clone()
+        };
+        for field in &mut rewindable.data {
+            field
+                .curve
+                .for_each_timeline_mut(|times| *times = times.to_vec().into());
+        }
+        rewindable.share_timelines();
+        let mut timelines = Vec::new();
+        for field in &mut rewindable.data {
+            field
+                .curve
+                .for_each_timeline_mut(|times| timelines.push(times.clone()));
+        }
+        assert!(std::sync::Arc::ptr_eq(&timelines[0], &timelines[1]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn borrowed_objects() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+        let count = buffer.rewindables.len();
+
+        let marble = buffer.marble()?;
+        assert_eq!(marble.game_object_name, "Marble");
+        assert!(marble.position().is_ok());
+        assert_eq!(buffer.powerups().count(), 2);
+        assert_eq!(buffer.bumpers().count(), 1);
+        assert_eq!(buffer.elevators().count(), 1);
+        assert_eq!(buffer.gems().count(), 2);
+        assert_eq!(buffer.checkpoints().count(), 1);
+        assert!(buffer.marble().is_ok());
+        assert_eq!(buffer.rewindables.len(), count);
+
+        let mut taken = buffer.clone();
+        assert_eq!(taken.get_powerups()?.len(), 2);
+        assert_eq!(taken.rewindables.len(), count - 2);
+
+        let objects = buffer.into_objects();
+        assert_eq!(objects.marbles.len(), 2);
+        assert_eq!(objects.powerups.len(), 2);
+        assert_eq!(objects.bumpers.len(), 1);
+        assert_eq!(objects.elevators.len(), 1);
+        assert_eq!(objects.gems.len(), 2);
+        assert_eq!(objects.checkpoints.len(), 1);
+        assert_eq!(objects.others.len(), count - 9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replay_objects() -> Result<(), ReplayError> {
+        #[derive(ReplayObject)]
+        #[replay_object(type_name = "GemPickup")]
+        #[replay_fields(
+            offset: Float = "Offset",
+            spin: UInt32 = "Spin",
+        )]
+        struct BrokenGem {
+            inner: Rewindable,
+        }
+
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+
+        let gems = buffer.objects::<Gem<_>>().collect::<Vec<_>>();
+        assert_eq!(gems.len(), 2);
+        assert!(gems.iter().all(|gem| gem.collected().is_ok()));
+        assert_eq!(buffer.objects::<Powerup<_>>().count(), 2);
+
+        let err = Gem::from_rewindable(buffer.marble()?.inner).err().unwrap();
+        assert!(matches!(err, ReplayError::MismatchedObjectType { .. }));
+
+        let gems = buffer.take_objects::<Gem>();
+        assert_eq!(gems.len(), 2);
+        assert_eq!(gems[0].rewindable().type_name, Gem::<Rewindable>::TYPE_NAME);
+        assert_eq!(buffer.objects::<Gem<_>>().count(), 0);
+
+        assert!(gems[0].validate().is_ok());
+        assert!(gems[0].offset().is_ok());
+        assert_eq!(
+            Gem::<Rewindable>::describe(),
+            "GemPickup { Collected: Bool, Offset: Vector2, Spin: UInt32 }"
+        );
+
+        let broken = BrokenGem::from_rewindable(gems[1].inner.clone())?;
+        let Err(ReplayError::InvalidObject { type_name, fields }) = broken.validate() else {
+            panic!("expected an invalid object");
+        };
+        assert_eq!(type_name, "GemPickup");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field.text, "Offset");
+        assert_eq!(fields[0].found, Some(RewindCurveType::Vector2));
+        assert!(broken.spin().is_ok());
+
+        let marble = buffer.marble()?;
+        assert!(marble.validate().is_ok());
+        assert_eq!(Marble::<Rewindable>::FIELDS.len(), 21);
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_fields() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+        let marble = buffer.marble()?;
+
+        let position = marble.field::<Vector3>("Position")?;
+        assert_eq!(
+            position.recent_curve.len(),
+            marble.position()?.recent_curve.len()
+        );
+        assert_eq!(marble.array_field::<u32>("EffectState")?.curves.len(), 3);
+        assert!(marble.field::<u16>("CollectedGems").is_ok());
+
+        assert!(matches!(
+            marble.field::<f32>("Position"),
+            Err(ReplayError::MismatchedCurveTypes {
+                lhs: RewindCurveType::Float,
+                rhs: RewindCurveType::Vector3
+            })
+        ));
+        assert!(matches!(
+            marble.array_field::<i32>("EffectState"),
+            Err(ReplayError::MismatchedCurveTypes {
+                lhs: RewindCurveType::Int32Array,
+                rhs: RewindCurveType::UInt32Array
+            })
+        ));
+        assert!(matches!(
+            marble.field::<f32>("Nope"),
+            Err(ReplayError::MissingField(field)) if field == "Nope"
+        ));
+        let name = String::from("Position");
+        assert!(marble.field::<Vector3>(&name).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_lookup() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+        let mut marble = buffer.get_marble()?;
+
+        let velocity = marble.find("Velocity").unwrap();
+        assert_eq!(velocity.text, "Velocity");
+        assert_eq!(
+            marble.find_by_index(velocity.index).unwrap().text,
+            "Velocity"
+        );
+        assert!(marble.find("Nope").is_none());
+
+        let position = marble.field_index.position("Velocity").unwrap();
+        assert_eq!(marble.data[position].text, "Velocity");
+        assert_eq!(
+            marble.field_index.position_by_index(velocity.index),
+            Some(position)
+        );
+        assert_eq!(marble.field_index.position("Nope"), None);
+
+        // point the index at a second "Position" field, which a linear search never reaches
+        let mut field = marble.find("Velocity").unwrap().clone();
+        field.text = "Position".to_owned();
+        marble.data.push(field);
+        let mut renamed = marble.data.clone();
+        renamed[0..marble.data.len() - 1]
+            .iter_mut()
+            .find(|d| d.text == "Position")
+            .unwrap()
+            .text = "Shadowed".to_owned();
+        marble.field_index = FieldIndex::new(&renamed);
+        assert_eq!(
+            marble.position()?.recent_curve.values(),
+            marble.velocity()?.recent_curve.values()
+        );
+
+        marble.data.pop();
+        marble.reindex();
+        assert_eq!(marble.position()?.recent_curve.len(), 120);
+
+        // a stale index falls back to a linear search
+        marble.data.reverse();
+        assert_eq!(marble.position()?.recent_curve.len(), 120);
+        marble.data.retain(|d| d.text != "Omega");
+        assert!(marble.omega().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_marbles() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let mut buffer = replay.decode_replay_buffer()?;
+
+        let names = buffer
+            .marbles()
+            .map(|m| m.game_object_name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Marble", "Ghost"]);
+
+        let ghost = buffer.marble_named("Ghost")?;
+        assert_eq!(ghost.position()?.recent_curve.len(), 60);
+        assert!(matches!(
+            buffer.marble_named("Nope"),
+            Err(ReplayError::NoMarbleNamed(name)) if name == "Nope"
+        ));
+        assert_eq!(buffer.local_marble()?.game_object_name, "Marble");
+
+        let index = replay.index_replay_buffer()?;
+        assert_eq!(index.get_marble_named("Ghost")?.game_object_name, "Ghost");
+
+        let mut other = buffer.clone();
+        other.rewindables.retain(|r| r.game_object_name != "Ghost");
+        assert_eq!(other.local_marble()?.game_object_name, "Marble");
+
+        for r in &mut buffer.rewindables {
+            if r.game_object_name == "Ghost" {
+                r.game_object_name = "Player2".to_owned();
+            }
+        }
+        assert!(matches!(
+            buffer.local_marble(),
+            Err(ReplayError::AmbiguousMarble(names)) if names == ["Marble", "Player2"]
+        ));
+
+        let mut buffer = replay.decode_replay_buffer()?;
+        assert_eq!(buffer.get_marbles()?.len(), 2);
+        assert!(matches!(
+            buffer.local_marble(),
+            Err(ReplayError::NoMarbleController)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn generic_objects() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+
+        let gems = buffer.generic_objects("GemPickup").collect::<Vec<_>>();
+        assert_eq!(gems.len(), 2);
+        let gem = &gems[0];
+        assert_eq!(gem.type_name(), "GemPickup");
+        assert_eq!(gem.fields().count(), 3);
+        assert!(gem.field::<bool>("Collected")?.sample(0.0).is_some());
+        assert_eq!(gem.curve("Offset")?.curve_type(), RewindCurveType::Vector2);
+        assert!(matches!(
+            gem.field::<f32>("Collected"),
+            Err(ReplayError::MismatchedCurveTypes { .. })
+        ));
+        assert!(matches!(
+            gem.field::<bool>("Nope"),
+            Err(ReplayError::MissingField(field)) if field == "Nope"
+        ));
+
+        let checkpoint = buffer.generic_objects("Checkpoint").next().unwrap();
+        assert_eq!(checkpoint.fields().count(), 0);
+        assert_eq!(buffer.generic_objects("Nope").count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let buffer = replay.decode_replay_buffer()?;
+        let schema = buffer.schema();
+
+        assert_eq!(schema.types.len(), 6);
+        let marble = &schema.types[ReplayBuffer::MARBLE_CONTROLLER];
+        assert_eq!(marble.instances, 2);
+        assert_eq!(marble.fields.len(), 21);
+        let position = &marble.fields["Position"];
+        assert_eq!(position.rewind_type(), RewindCurveType::Vector3);
+        assert!(!position.is_conflicting());
+        assert_eq!((position.samples.min, position.samples.max), (60, 120));
+        let line = format!(
+            "Position: [{}] Vector3 (60..=120 samples)",
+            position.index()
+        );
+        assert!(schema.to_string().contains(&line));
+        assert!(schema.diff(&schema).is_empty());
+
+        let mut merged = schema.clone();
+        merged.merge(&schema);
+        assert_eq!(merged.buffers, 2);
+        assert_eq!(merged.types[ReplayBuffer::MARBLE_CONTROLLER].instances, 4);
+
+        let mut changed = buffer.clone();
+        changed.rewindables.retain(|r| r.type_name != "Checkpoint");
+        for rewindable in &mut changed.rewindables {
+            for data in &mut rewindable.data {
+                if data.text == "Spin" {
+                    data.text = "Rotation".into();
+                } else if data.text == "StrikeTimeLeft" {
+                    data.index += 1;
+                }
+            }
+        }
+        let spin = schema.types["GemPickup"].fields["Spin"].index();
+        let changes = schema.diff(&changed.schema());
+        let changes = changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            [
+                "~ BumperController.StrikeTimeLeft: [0] Float -> [1] Float".to_string(),
+                "- Checkpoint".to_string(),
+                "- GemPickup.Spin".to_string(),
+                format!("+ GemPickup.Rotation: [{spin}] UInt32"),
+            ]
+        );
+
+        // a field seen with different types within one schema keeps every variant
+        let mut conflicting = buffer.clone();
+        let ghost = conflicting
+            .rewindables
+            .iter_mut()
+            .find(|r| r.game_object_name == "Ghost")
+            .unwrap();
+        ghost
+            .data
+            .iter_mut()
+            .find(|d| d.text == "Position")
+            .unwrap()
+            .rewind_type = RewindCurveType::Vector2;
+        let conflicting = conflicting.schema();
+        let position = &conflicting.types[ReplayBuffer::MARBLE_CONTROLLER].fields["Position"];
+        assert!(position.is_conflicting());
+        assert_eq!(position.variants.len(), 2);
+        let index = position.index();
+        assert_eq!(
+            schema
+                .diff(&conflicting)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [format!(
+                "~ MarbleController.Position: [{index}] Vector3 -> [{index}] Vector3 | [{index}] Vector2"
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_limits() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let options = ParseOptions {
+            max_samples_per_curve: 4,
+            ..Default::default()
+        };
+
+        let err = replay.decode_replay_buffer_with(&options).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            ReplayError::LimitExceeded {
+                limit: Limit::SamplesPerCurve,
+                ..
+            }
+        ));
+
+        let context = err.context().unwrap();
+        assert!(context.game_object_name.is_some());
+        assert!(context.type_name.is_some());
+        assert!(context.field.is_some());
+
+        // huge counts without any limits run out of data instead of allocating up front
+        let (header, compressed) = replay.data.replay_buffer.split_at(8);
+        let data = miniz_oxide::inflate::decompress_to_vec(compressed)?;
+        let text = b"\x08Velocity";
+        let velocity = data.windows(text.len()).position(|w| w == text).unwrap() + text.len();
+        assert_eq!(data[velocity + 9..velocity + 13], 120i32.to_le_bytes());
+        for pos in [0, velocity + 9] {
+            let mut data = data.clone();
+            data[pos..pos + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+            let mut replay = replay.clone();
+            replay.data.replay_buffer =
+                [header, &miniz_oxide::deflate::compress_to_vec(&data, 6)].concat();
+
+            let err = replay
+                .decode_replay_buffer_with(&ParseOptions::unlimited())
+                .unwrap_err();
+            assert!(matches!(err.root(), ReplayError::DataDecode(_)));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn salvage_truncated() -> Result<(), ReplayError> {
+        let mut replay = Replay::parse(REPLAY_FILE)?;
+        let full = replay.decode_replay_buffer()?;
+
+        let len = replay.data.replay_buffer.len();
+        replay.data.replay_buffer.truncate(len / 2);
+        assert!(replay.decode_replay_buffer().is_err());
+
+        let salvaged = replay.decode_replay_buffer_lenient()?;
+        assert!(!salvaged.is_complete());
+        assert!(!salvaged.buffer.rewindables.is_empty());
+        assert!(salvaged.buffer.rewindables.len() <= full.rewindables.len());
+        assert_eq!(
+            salvaged.buffer.rewindables[0].game_object_name,
+            full.rewindables[0].game_object_name
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn salvage_unknown_curve_type() -> Result<(), ReplayError> {
+        let mut replay = Replay::parse(REPLAY_FILE)?;
+        let full = replay.decode_replay_buffer()?;
+
+        // give the marble's velocity an unknown curve type, its fitter still knows it's a `Vector3`
+        let (header, compressed) = replay.data.replay_buffer.split_at(8);
+        let mut data = miniz_oxide::inflate::decompress_to_vec(compressed)?;
+        let text = b"\x08Velocity";
+        let pos = data.windows(text.len()).position(|w| w == text).unwrap() + text.len();
+        data[pos..pos + 4].copy_from_slice(&42i32.to_le_bytes());
+        replay.data.replay_buffer =
+            [header, &miniz_oxide::deflate::compress_to_vec(&data, 6)].concat();
+        assert!(replay.decode_replay_buffer().is_err());
+
+        let salvaged = replay.decode_replay_buffer_lenient()?;
+        assert_eq!(salvaged.diagnostics.len(), 1);
+        let Diagnostic::SkippedField { error } = &salvaged.diagnostics[0] else {
+            panic!("expected a skipped field");
+        };
+        assert!(matches!(
+            error.root(),
+            ReplayError::UnknownCurveType { value: 42, .. }
+        ));
+        assert_eq!(error.context().unwrap().field.as_deref(), Some("Velocity"));
+
+        assert_eq!(salvaged.buffer.rewindables.len(), full.rewindables.len());
+        let marble = &salvaged.buffer.rewindables[0];
+        assert!(marble.find("Velocity").is_none());
+        assert!(marble.find("Omega").is_some());
+        assert_eq!(marble.data.len(), full.rewindables[0].data.len() - 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn streaming() -> Result<(), ReplayError> {
+        let replay = Replay::from_reader(REPLAY_FILE)?;
+        let buffer = ReplayBuffer::read_from(
+            replay.data.replay_buffer.as_slice(),
+            &ParseOptions::default(),
+        )?;
+        assert_eq!(buffer.encode()?, replay.decode_replay_buffer()?.encode()?);
+
+        let options = ParseOptions {
+            max_decompressed_size: 64,
+            ..Default::default()
+        };
+        assert!(matches!(
+            replay
+                .decode_replay_buffer_with(&options)
+                .unwrap_err()
+                .root(),
+            ReplayError::LimitExceeded {
+                limit: Limit::DecompressedSize,
+                ..
+            }
+        ));
+
+        // reading on after the limit was hit keeps failing
+        let mut inflater = inflate_reader::InflateReader::new(&replay.data.replay_buffer[8..], 64);
+        let mut out = [0; 128];
+        assert!(inflater.read(&mut out).is_err());
+        assert!(inflater.read(&mut out).is_err());
+
+        // a failing source surfaces as an inflate error, not as a generic io error
+        let failing = replay.data.replay_buffer[..8].chain(FailingReader);
+        assert!(matches!(
+            ReplayBufferIndex::read_from(failing, &ParseOptions::default()),
+            Err(ReplayError::Inflate(_))
+        ));
+        let failing = replay.data.replay_buffer[..8].chain(FailingReader);
+        assert!(matches!(
+            ReplayBuffer::read_from(failing, &ParseOptions::default())
+                .unwrap_err()
+                .root(),
+            ReplayError::Inflate(_)
+        ));
+
+        Ok(())
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn borrowed() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let replay_ref = ReplayRef::parse(REPLAY_FILE)?;
+
+        assert_eq!(replay_ref.data.player, replay.data.player);
+        assert_eq!(replay_ref.data.replay_buffer, replay.data.replay_buffer);
+        assert_eq!(replay_ref.into_owned().to_bytes()?, REPLAY_FILE);
+        assert_eq!(rmp_serde::to_vec_named(&replay_ref)?, REPLAY_FILE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn index() -> Result<(), ReplayError> {
+        let replay = Replay::parse(REPLAY_FILE)?;
+        let index = replay.index_replay_buffer()?;
+        let buffer = replay.decode_replay_buffer()?;
+
+        assert_eq!(index.rewindables.len(), buffer.rewindables.len());
+        assert_eq!(index.decode_all()?.encode()?, buffer.encode()?);
+
+        let marble = index.get_marble()?;
+        let entry = &index.rewindables[0];
+        let position = index.decode_field(entry, entry.field("Position").unwrap())?;
+        assert_eq!(position.text, "Position");
+        assert_eq!(
+            marble.position()?.recent_curve.len(),
+            match &position.curve {
+                IRewindCurve::Vector3(fitter) => fitter.recent_curve.len(),
+                _ => unreachable!(),
+            }
+        );
+
+        Ok(())
+    }
 }
@@ -1,16 +1,12 @@
-use crate::{RewindCurveFitter, Rewindable, field};
+use crate::{ReplayObject, Rewindable};
 
-/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_bumpers`](crate::ReplayBuffer::get_bumpers).  
-#[derive(Debug, Clone)]
-pub struct Bumper {
-    pub inner: Rewindable,
-}
-
-impl Bumper {
-    field!(
-        strike_time_left,
-        &RewindCurveFitter<f32>,
-        "StrikeTimeLeft",
-        Float
-    );
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_bumpers`](crate::ReplayBuffer::get_bumpers)
+/// or borrowed from [`ReplayBuffer::bumpers`](crate::ReplayBuffer::bumpers).  
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "BumperController")]
+#[replay_fields(
+    strike_time_left: Float = "StrikeTimeLeft",
+)]
+pub struct Bumper<R = Rewindable> {
+    pub inner: R,
 }
@@ -0,0 +1,11 @@
+use crate::{ReplayObject, Rewindable};
+
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_checkpoints`](crate::ReplayBuffer::get_checkpoints)
+/// or borrowed from [`ReplayBuffer::checkpoints`](crate::ReplayBuffer::checkpoints).  
+///
+/// Checkpoints record no fields, only their `game_object_name` & `ref_pos`.
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "Checkpoint")]
+pub struct Checkpoint<R = Rewindable> {
+    pub inner: R,
+}
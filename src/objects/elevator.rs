@@ -1,15 +1,16 @@
-use crate::{RewindCurveFitter, Rewindable, field};
+use crate::{ReplayObject, Rewindable};
 
-/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_elevators`](crate::ReplayBuffer::get_elevators).  
-#[derive(Debug, Clone)]
-pub struct Elevator {
-    pub inner: Rewindable,
-}
-
-impl Elevator {
-    field!(t, &RewindCurveFitter<i32>, "T", Int);
-    field!(collapsing, &RewindCurveFitter<bool>, "Collapsing", Bool);
-    field!(stop_time, &RewindCurveFitter<f32>, "StopTime", Float);
-    field!(enable_bob, &RewindCurveFitter<bool>, "EnableBob", Bool);
-    field!(global_time, &RewindCurveFitter<i32>, "GlobalTime", Int);
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_elevators`](crate::ReplayBuffer::get_elevators)
+/// or borrowed from [`ReplayBuffer::elevators`](crate::ReplayBuffer::elevators).  
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "ElevatorMover")]
+#[replay_fields(
+    t: Int = "T",
+    collapsing: Bool = "Collapsing",
+    stop_time: Float = "StopTime",
+    enable_bob: Bool = "EnableBob",
+    global_time: Int = "GlobalTime",
+)]
+pub struct Elevator<R = Rewindable> {
+    pub inner: R,
 }
@@ -0,0 +1,14 @@
+use crate::{ReplayObject, Rewindable};
+
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_gems`](crate::ReplayBuffer::get_gems)
+/// or borrowed from [`ReplayBuffer::gems`](crate::ReplayBuffer::gems).  
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "GemPickup")]
+#[replay_fields(
+    collected: Bool = "Collected",
+    offset: Vector2 = "Offset",
+    spin: UInt32 = "Spin",
+)]
+pub struct Gem<R = Rewindable> {
+    pub inner: R,
+}
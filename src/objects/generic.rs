@@ -0,0 +1,81 @@
+use std::borrow::Borrow;
+
+use crate::{
+    ArrayCurveValue, CurveValue, IRewindCurve, ReplayError, RewindCurveFitter,
+    RewindCurveFitterArray, Rewindable, RewindableData, Vector3,
+};
+
+/// A wrapper for a [`Rewindable`] of any `type_name`, looking fields up by their `text` at runtime.
+///
+/// Used for everything without a typed wrapper, or to read fields a typed wrapper doesn't know about.  
+/// Obtained through [`ReplayBuffer::generic_objects`](crate::ReplayBuffer::generic_objects)
+/// or [`ReplayObjects::others`](crate::ReplayObjects::others).
+///
+/// ```ignore
+/// for gem in buffer.generic_objects("GemPickup") {
+///     let collected = gem.field::<bool>("Collected")?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GenericObject<R = Rewindable> {
+    pub inner: R,
+}
+
+impl<R: Borrow<Rewindable>> GenericObject<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped [`Rewindable`]
+    pub fn rewindable(&self) -> &Rewindable {
+        self.inner.borrow()
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.rewindable().type_name
+    }
+
+    pub fn game_object_name(&self) -> &str {
+        &self.rewindable().game_object_name
+    }
+
+    pub fn ref_pos(&self) -> Vector3 {
+        self.rewindable().ref_pos
+    }
+
+    /// Iterates over every field in the order they were recorded.
+    pub fn fields(&self) -> impl Iterator<Item = &RewindableData> {
+        self.rewindable().data.iter()
+    }
+
+    /// Finds the field with the matching [`text`](RewindableData::text).
+    pub fn get(&self, text: &str) -> Option<&RewindableData> {
+        self.rewindable().find(text)
+    }
+
+    /// Finds the field with the matching [`text`](RewindableData::text) & returns its curve.
+    pub fn curve(&self, text: &str) -> Result<&IRewindCurve, ReplayError> {
+        self.get(text)
+            .map(|data| &data.curve)
+            .ok_or_else(|| ReplayError::MissingField(text.to_owned()))
+    }
+
+    /// See [`Rewindable::field`].
+    pub fn field<T: CurveValue>(&self, text: &str) -> Result<&RewindCurveFitter<T>, ReplayError> {
+        self.rewindable().field(text)
+    }
+
+    /// See [`Rewindable::array_field`].
+    pub fn array_field<T: ArrayCurveValue>(
+        &self,
+        text: &str,
+    ) -> Result<&RewindCurveFitterArray<T>, ReplayError> {
+        self.rewindable().array_field(text)
+    }
+}
+
+impl<R: Borrow<Rewindable>> From<R> for GenericObject<R> {
+    fn from(inner: R) -> Self {
+        Self::new(inner)
+    }
+}
@@ -1,119 +1,53 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    borrow::{Borrow, BorrowMut},
+    ops::{Deref, DerefMut},
+};
 
-use crate::{Quaternion, RewindCurveFitter, RewindCurveFitterArray, Rewindable, Vector3, field};
+use crate::{ReplayObject, Rewindable};
 
-/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_marble`](crate::ReplayBuffer::get_marble).  
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_marble`](crate::ReplayBuffer::get_marble)
+/// or borrowed from [`ReplayBuffer::marble`](crate::ReplayBuffer::marble).  
 ///
 /// This type ensures that it is in fact a `MarbleController` & exposes some *QoL* functions related to the marble
-#[derive(Debug, Clone)]
-pub struct Marble {
-    pub inner: Rewindable,
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "MarbleController")]
+#[replay_fields(
+    starting_remaining_ticks: Int = "StartingRemainingTicks",
+    mode: Int = "Mode",
+    invokable_effect_id: UShort = "InvokableEffectId",
+    invokable_source_id: UShort = "InvokableSourceId",
+    effect_state: UInt32Array = "EffectState",
+    effect_ticks: Int32Array = "EffectTicks",
+    qw: Quaternion = "qW",
+    gravity_quat: Quaternion = "GravityQuat",
+    position: Vector3 = "Position",
+    velocity: Vector3 = "Velocity",
+    omega: Vector3 = "Omega",
+    bonus_time: Float = "BonusTime",
+    time_since_contact: Float = "TimeSinceContact",
+    best_contact_normal: Vector3 = "BestContactNormal",
+    best_contact_surface_velocity: Vector3 = "BestContactSurfaceVelocity",
+    elapsed_time: Float = "ElapsedTime",
+    collected_gems: UShort = "CollectedGems",
+    mega_marble_size_scale: Float = "MegaMarbleSizeScale",
+    done_first_bounce: Bool = "DoneFirstBounce",
+    blast_cooldown: Float = "BlastCooldown",
+    respawn_counter: Int = "RespawnCounter",
+)]
+pub struct Marble<R = Rewindable> {
+    pub inner: R,
 }
 
-impl Marble {
-    field!(
-        starting_remaining_ticks,
-        &RewindCurveFitter<i32>,
-        "StartingRemainingTicks",
-        Int
-    );
-    field!(mode, &RewindCurveFitter<i32>, "Mode", Int);
-    field!(
-        invokable_effect_id,
-        &RewindCurveFitter<u16>,
-        "InvokableEffectId",
-        UShort
-    );
-    field!(
-        invokable_source_id,
-        &RewindCurveFitter<u16>,
-        "InvokableSourceId",
-        UShort
-    );
-    field!(
-        effect_state,
-        &RewindCurveFitterArray<u32>,
-        "EffectState",
-        UInt32Array
-    );
-    field!(
-        effect_ticks,
-        &RewindCurveFitterArray<i32>,
-        "EffectTicks",
-        Int32Array
-    );
-    field!(qw, &RewindCurveFitter<Quaternion>, "qW", Quaternion);
-    field!(
-        gravity_quat,
-        &RewindCurveFitter<Quaternion>,
-        "GravityQuat",
-        Quaternion
-    );
-    field!(position, &RewindCurveFitter<Vector3>, "Position", Vector3);
-    field!(velocity, &RewindCurveFitter<Vector3>, "Velocity", Vector3);
-    field!(omega, &RewindCurveFitter<Vector3>, "Omega", Vector3);
-    field!(bonus_time, &RewindCurveFitter<f32>, "BonusTime", Float);
-    field!(
-        time_since_contact,
-        &RewindCurveFitter<f32>,
-        "TimeSinceContact",
-        Float
-    );
-    field!(
-        best_contact_normal,
-        &RewindCurveFitter<Vector3>,
-        "BestContactNormal",
-        Vector3
-    );
-    field!(
-        best_contact_surface_velocity,
-        &RewindCurveFitter<Vector3>,
-        "BestContactSurfaceVelocity",
-        Vector3
-    );
-    field!(elapsed_time, &RewindCurveFitter<f32>, "ElapsedTime", Float);
-    field!(
-        collected_gems,
-        &RewindCurveFitter<u16>,
-        "CollectedGems",
-        UShort
-    );
-    field!(
-        mega_marble_size_scale,
-        &RewindCurveFitter<f32>,
-        "MegaMarbleSizeScale",
-        Float
-    );
-    field!(
-        done_first_bounce,
-        &RewindCurveFitter<bool>,
-        "DoneFirstBounce",
-        Bool
-    );
-    field!(
-        blast_cooldown,
-        &RewindCurveFitter<f32>,
-        "BlastCooldown",
-        Float
-    );
-    field!(
-        respawn_counter,
-        &RewindCurveFitter<i32>,
-        "RespawnCounter",
-        Int
-    );
-}
-
-impl Deref for Marble {
+impl<R: Borrow<Rewindable>> Deref for Marble<R> {
     type Target = Rewindable;
 
     fn deref(&self) -> &Self::Target {
-        &self.inner
+        self.inner.borrow()
     }
 }
 
-impl DerefMut for Marble {
+impl<R: BorrowMut<Rewindable>> DerefMut for Marble<R> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.inner
+        self.inner.borrow_mut()
     }
 }
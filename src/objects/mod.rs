@@ -1,24 +1,181 @@
+use std::{borrow::Borrow, fmt::Display};
+
+use crate::{ReplayError, RewindCurveType, Rewindable};
+
 pub mod bumper;
+pub mod checkpoint;
 pub mod elevator;
+pub mod gem;
+pub mod generic;
 pub mod marble;
 pub mod powerup;
 
+/// A typed wrapper around a [`Rewindable`] of a single `type_name`.
+///
+/// `R` is what the wrapper holds, an owned [`Rewindable`] by default or a `&Rewindable` when borrowing.  
+/// Wrappers are usually obtained through [`ReplayBuffer::objects`](crate::ReplayBuffer::objects)
+/// or [`ReplayBuffer::take_objects`](crate::ReplayBuffer::take_objects).
+///
+/// This is usually derived with [`derive(ReplayObject)`](macro@crate::ReplayObject), which also generates typed field accessors.  
+/// When implementing it by hand, the [`field!`](crate::field) macro can generate accessors instead,
+/// it expects the wrapped value in a field called `inner`.
+///
+/// ```
+/// use std::borrow::Borrow;
+/// use miuu_replay::{ReplayObject, RewindCurveFitter, Rewindable, field};
+///
+/// pub struct Gem<R = Rewindable> {
+///     pub inner: R,
+/// }
+///
+/// impl<R: Borrow<Rewindable>> Gem<R> {
+///     field!(collected, &RewindCurveFitter<bool>, "Collected", Bool);
+/// }
+///
+/// impl<R: Borrow<Rewindable>> ReplayObject<R> for Gem<R> {
+///     const TYPE_NAME: &'static str = "GemPickup";
+///
+///     fn from_rewindable_unchecked(inner: R) -> Self {
+///         Self { inner }
+///     }
+///
+///     fn rewindable(&self) -> &Rewindable {
+///         self.inner.borrow()
+///     }
+/// }
+/// ```
+pub trait ReplayObject<R: Borrow<Rewindable> = Rewindable>: Sized {
+    /// The `type_name` of the [`Rewindable`]'s this wraps.
+    const TYPE_NAME: &'static str;
+
+    /// The fields the wrapper expects, checked by [`validate`](Self::validate).
+    const FIELDS: &'static [ObjectField] = &[];
+
+    /// Wraps `rewindable` without checking its `type_name`.
+    fn from_rewindable_unchecked(rewindable: R) -> Self;
+
+    /// The wrapped [`Rewindable`]
+    fn rewindable(&self) -> &Rewindable;
+
+    /// Wraps `rewindable` if its `type_name` matches [`TYPE_NAME`](Self::TYPE_NAME).
+    fn from_rewindable(rewindable: R) -> Result<Self, ReplayError> {
+        let type_name = &rewindable.borrow().type_name;
+        if type_name != Self::TYPE_NAME {
+            return Err(ReplayError::MismatchedObjectType {
+                expected: Self::TYPE_NAME,
+                found: type_name.clone(),
+            });
+        }
+
+        Ok(Self::from_rewindable_unchecked(rewindable))
+    }
+
+    /// Checks that every field in [`FIELDS`](Self::FIELDS) exists with the right [`RewindCurveType`].
+    ///
+    /// Every field is checked in a single pass,
+    /// all that are missing or mistyped are returned together in [`ReplayError::InvalidObject`].
+    fn validate(&self) -> Result<(), ReplayError> {
+        let rewindable = self.rewindable();
+        let fields = Self::FIELDS
+            .iter()
+            .filter_map(|field| {
+                let found = rewindable.find(field.text).map(|data| data.rewind_type);
+                (found != Some(field.rewind_type)).then_some(FieldMismatch {
+                    field: *field,
+                    found,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(ReplayError::InvalidObject {
+                type_name: Self::TYPE_NAME,
+                fields,
+            })
+        }
+    }
+
+    /// A compact description of the expected schema, like `GemPickup { Collected: Bool, Offset: Vector3 }`.
+    fn describe() -> String {
+        let fields = Self::FIELDS
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        format!("{} {{ {} }}", Self::TYPE_NAME, fields.join(", "))
+    }
+}
+
+/// A single field expected by a [`ReplayObject`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectField {
+    /// The name of the accessor.
+    pub name: &'static str,
+    /// The [`text`](crate::RewindableData::text) of the field.
+    pub text: &'static str,
+    pub rewind_type: RewindCurveType,
+}
+
+impl Display for ObjectField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:?}", self.text, self.rewind_type)
+    }
+}
+
+/// A field that failed [`ReplayObject::validate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMismatch {
+    pub field: ObjectField,
+    /// The [`RewindCurveType`] the field actually has, `None` if it's missing.
+    pub found: Option<RewindCurveType>,
+}
+
+impl Display for FieldMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(found) => write!(f, "{} is {found:?}", self.field),
+            None => write!(f, "{} is missing", self.field),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::{IRewindCurve, ReplayError, RewindCurveType, Rewindable};
+
+    /// Looks up a typed field for the accessors generated by `derive(ReplayObject)`.
+    pub fn find_field<'a, T>(
+        rewindable: &'a Rewindable,
+        text: &'static str,
+        rewind_type: RewindCurveType,
+        get: impl FnOnce(&'a IRewindCurve) -> Option<&'a T>,
+    ) -> Result<&'a T, ReplayError> {
+        let val = rewindable
+            .find(text)
+            .ok_or_else(|| ReplayError::MissingField(text.to_owned()))?;
+
+        get(&val.curve).ok_or(ReplayError::MismatchedCurveTypes {
+            lhs: rewind_type,
+            rhs: val.rewind_type,
+        })
+    }
+}
+
 pub(crate) mod field_macro {
     #[macro_export]
     macro_rules! field {
         ($fn_name:ident, &$return_name:ty, $field_str:expr, $rewind_type:ident) => {
-            pub fn $fn_name(&self) -> Result<&$return_name, crate::ReplayError> {
-                let val = self
-                    .inner
-                    .data
-                    .iter()
-                    .find(|d| d.text == $field_str)
-                    .ok_or(crate::ReplayError::MissingField($field_str))?;
+            pub fn $fn_name(&self) -> Result<&$return_name, $crate::ReplayError> {
+                let val = ::std::borrow::Borrow::<$crate::Rewindable>::borrow(&self.inner)
+                    .find($field_str)
+                    .ok_or_else(|| $crate::ReplayError::MissingField($field_str.to_owned()))?;
 
                 match &val.curve {
-                    crate::rewind_curve::IRewindCurve::$rewind_type(v) => Ok(v),
-                    _ => Err(crate::ReplayError::MismatchedCurveTypes {
-                        lhs: crate::RewindCurveType::$rewind_type,
+                    $crate::IRewindCurve::$rewind_type(v) => Ok(v),
+                    _ => Err($crate::ReplayError::MismatchedCurveTypes {
+                        lhs: $crate::RewindCurveType::$rewind_type,
                         rhs: val.rewind_type,
                     }),
                 }
@@ -1,17 +1,13 @@
-use crate::{RewindCurveFitter, Rewindable, field};
+use crate::{ReplayObject, Rewindable};
 
-/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_powerups`](crate::ReplayBuffer::get_powerups).  
-#[derive(Debug, Clone)]
-pub struct Powerup {
-    pub inner: Rewindable,
-}
-
-impl Powerup {
-    field!(
-        available_for_pickup,
-        &RewindCurveFitter<bool>,
-        "AvailableForPickup",
-        Bool
-    );
-    field!(point_value, &RewindCurveFitter<u16>, "PointValue", UShort);
+/// A wrapper type for [`Rewindable`] that comes from [`ReplayBuffer::get_powerups`](crate::ReplayBuffer::get_powerups)
+/// or borrowed from [`ReplayBuffer::powerups`](crate::ReplayBuffer::powerups).  
+#[derive(Debug, Clone, ReplayObject)]
+#[replay_object(type_name = "Powerup")]
+#[replay_fields(
+    available_for_pickup: Bool = "AvailableForPickup",
+    point_value: UShort = "PointValue",
+)]
+pub struct Powerup<R = Rewindable> {
+    pub inner: R,
 }
@@ -0,0 +1,117 @@
+use std::io::Read;
+
+use csharp_binary_encoding::{BinaryReader, DataDecodeError, InvalidDataError};
+
+use crate::ReplayError;
+
+/// The most elements that get preallocated for a count read from the buffer,
+/// anything beyond that grows as the data actually arrives.
+pub(crate) const MAX_PREALLOCATION: usize = 4096;
+
+/// Creates a [`Vec`] for `count` elements, preallocating at most [`MAX_PREALLOCATION`] of them.
+pub(crate) fn preallocate<T>(count: usize) -> Vec<T> {
+    Vec::with_capacity(count.min(MAX_PREALLOCATION))
+}
+
+/// Limits that are enforced while decoding a `replay_buffer`.
+///
+/// Every count within the buffer is checked against these before anything is allocated,
+/// so a hostile file fails early with [`ReplayError::LimitExceeded`] instead of exhausting memory.
+///
+/// The [`Default`] limits are far above anything the game records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The maximum size in bytes of the decompressed `replay_buffer`.
+    pub max_decompressed_size: usize,
+    /// The maximum amount of [`Rewindable`](crate::Rewindable)'s in a buffer.
+    pub max_rewindables: usize,
+    /// The maximum amount of [`RewindableData`](crate::RewindableData) fields per rewindable.
+    pub max_fields: usize,
+    /// The maximum amount of curves within a [`RewindCurveFitterArray`](crate::RewindCurveFitterArray).
+    pub max_array_length: usize,
+    /// The maximum amount of samples within a single [`RewindCurve`](crate::RewindCurve).
+    pub max_samples_per_curve: usize,
+    /// The maximum length in bytes of any string.
+    pub max_string_length: usize,
+}
+
+/// The different limits within [`ParseOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    DecompressedSize,
+    Rewindables,
+    Fields,
+    ArrayLength,
+    SamplesPerCurve,
+    StringLength,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_decompressed_size: 512 * 1024 * 1024,
+            max_rewindables: 1 << 16,
+            max_fields: 1 << 10,
+            max_array_length: 1 << 10,
+            max_samples_per_curve: 1 << 22,
+            max_string_length: 1 << 12,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options without any limits, only use these on trusted input.
+    pub fn unlimited() -> Self {
+        Self {
+            max_decompressed_size: usize::MAX,
+            max_rewindables: usize::MAX,
+            max_fields: usize::MAX,
+            max_array_length: usize::MAX,
+            max_samples_per_curve: usize::MAX,
+            max_string_length: usize::MAX,
+        }
+    }
+
+    /// Returns the configured maximum for `limit`.
+    pub fn max(&self, limit: Limit) -> usize {
+        match limit {
+            Limit::DecompressedSize => self.max_decompressed_size,
+            Limit::Rewindables => self.max_rewindables,
+            Limit::Fields => self.max_fields,
+            Limit::ArrayLength => self.max_array_length,
+            Limit::SamplesPerCurve => self.max_samples_per_curve,
+            Limit::StringLength => self.max_string_length,
+        }
+    }
+
+    /// Checks that `value` is positive & within `limit`.
+    pub(crate) fn check(&self, limit: Limit, value: i64) -> Result<usize, ReplayError> {
+        let max = self.max(limit);
+        match usize::try_from(value) {
+            Ok(value) if value <= max => Ok(value),
+            _ => Err(ReplayError::LimitExceeded { limit, value, max }),
+        }
+    }
+
+    /// Reads an [`i32`] count and checks it against `limit`.
+    pub(crate) fn read_count<R: Read>(
+        &self,
+        reader: &mut BinaryReader<R>,
+        limit: Limit,
+    ) -> Result<usize, ReplayError> {
+        let count = reader.read_i32()?;
+        self.check(limit, count as i64)
+    }
+
+    /// Reads a string while making sure its length is within [`max_string_length`](Self::max_string_length).
+    pub(crate) fn read_string<R: Read>(
+        &self,
+        reader: &mut BinaryReader<R>,
+    ) -> Result<String, ReplayError> {
+        let len = reader.read_7_bit_encoded_int()?;
+        let len = self.check(Limit::StringLength, len as i64)?;
+
+        String::from_utf8(reader.read_bytes(len)?)
+            .map_err(|_| DataDecodeError::from(InvalidDataError::InvalidUtf8).into())
+    }
+}
@@ -16,4 +16,62 @@ impl Quaternion {
             w: values.3,
         }
     }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn magnitude(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Quaternion {
+        let magnitude = self.magnitude();
+        if magnitude > f32::EPSILON {
+            self.scale(1.0 / magnitude)
+        } else {
+            Quaternion::new((0.0, 0.0, 0.0, 1.0))
+        }
+    }
+
+    /// Spherically interpolates towards `other` along the shortest path, `t` isn't clamped.
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Quaternion {
+        let mut dot = self.dot(other);
+        let other = if dot < 0.0 {
+            dot = -dot;
+            other.scale(-1.0)
+        } else {
+            *other
+        };
+
+        // nearly identical rotations, fall back to a normalized lerp to avoid dividing by ~0
+        if dot > 0.9995 {
+            return self.scale(1.0 - t).plus(&other.scale(t)).normalize();
+        }
+
+        let theta = dot.min(1.0).acos();
+        let sin_theta = theta.sin();
+        let from = ((1.0 - t) * theta).sin() / sin_theta;
+        let to = (t * theta).sin() / sin_theta;
+
+        self.scale(from).plus(&other.scale(to))
+    }
+
+    fn scale(&self, factor: f32) -> Quaternion {
+        Quaternion::new((
+            self.x * factor,
+            self.y * factor,
+            self.z * factor,
+            self.w * factor,
+        ))
+    }
+
+    fn plus(&self, other: &Quaternion) -> Quaternion {
+        Quaternion::new((
+            self.x + other.x,
+            self.y + other.y,
+            self.z + other.z,
+            self.w + other.w,
+        ))
+    }
 }
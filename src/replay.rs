@@ -1,11 +1,14 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::ReplayError;
 
 /// A parsed `.replay` file  
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Replay {
     #[serde(rename = "typeId")]
     pub type_id: i32,
@@ -16,19 +19,19 @@ pub struct Replay {
 }
 
 /// Replay specific data within a [`Replay`]
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct ReplayData {
     pub level: String,
     pub player: String,
     pub score: f64,
     pub cosmetics: ReplayCosmetics,
     /// The raw `replay_buffer`, use [`decode_replay_buffer`](Replay::decode_replay_buffer) to decode this data.  
-    #[serde(rename = "replayBuffer")]
+    #[serde(rename = "replayBuffer", serialize_with = "serialize_bin")]
     pub replay_buffer: Vec<u8>,
 }
 
 /// All the different cosmetics within a [`ReplayData`]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ReplayCosmetics {
     pub skin: String,
     pub trail: String,
@@ -70,4 +73,26 @@ impl Replay {
     pub fn parse(data: &[u8]) -> Result<Self, ReplayError> {
         Ok(rmp_serde::from_slice(data)?)
     }
+
+    /// Parses a raw replay file from `reader` into a [`Replay`].  
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ReplayError> {
+        Ok(rmp_serde::from_read(reader)?)
+    }
+
+    /// Serializes the [`Replay`] back into the raw `.replay` file format.  
+    ///
+    /// An unmodified [`Replay`] serializes to the exact same bytes it was parsed from.  
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError> {
+        Ok(rmp_serde::to_vec_named(self)?)
+    }
+
+    /// Serializes the [`Replay`] into the raw `.replay` file format and writes it to `writer`.  
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), ReplayError> {
+        Ok(rmp_serde::encode::write_named(writer, self)?)
+    }
+}
+
+/// The game stores the `replay_buffer` as MessagePack `bin` data and not as an array of integers.
+pub(crate) fn serialize_bin<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(data)
 }
@@ -1,51 +1,39 @@
-use csharp_binary_encoding::BinaryReader;
-use miniz_oxide::inflate::decompress_to_vec;
+use std::io::Read;
+
+use csharp_binary_encoding::{BinaryReader, BinaryWriter};
+use miniz_oxide::deflate::{CompressionLevel, compress_to_vec};
 
 use crate::{
-    Elevator, Marble, Powerup, Replay, ReplayError, Rewindable, RewindableData, Vector3,
+    Checkpoint, Elevator, Gem, GenericObject, Marble, ParseOptions, Powerup, Replay, ReplayError,
+    ReplayObject, Rewindable,
+    buffer_reader::BufferReader,
     objects::bumper::Bumper,
+    parse_options::{Limit, preallocate},
 };
 
 impl Replay {
     /// Decodes the raw `replay_buffer` within a [`Replay`] to a [`ReplayBuffer`]
+    ///
+    /// This uses the [`Default`] [`ParseOptions`], see [`decode_replay_buffer_with`](Self::decode_replay_buffer_with).  
     pub fn decode_replay_buffer(&self) -> Result<ReplayBuffer, ReplayError> {
-        let mut reader = BinaryReader::new(self.data.replay_buffer.as_slice());
-        let header = ReplayHeader {
-            session: reader.read_i32()?,
-            version: reader.read_i32()?,
-        };
-
-        let compressed_len = self.data.replay_buffer.len() - reader.num_bytes_read() as usize;
-        let compressed_data = reader.read_bytes(compressed_len)?;
-        let data = decompress_to_vec(&compressed_data)?;
-        let mut reader = BinaryReader::new(data.as_slice());
-
-        let rewindable_count = reader.read_i32()?;
-        let mut rewindables = Vec::with_capacity(rewindable_count as usize);
-
-        for _ in 0..rewindable_count {
-            let game_object_name = reader.read_string()?;
-            let type_name = reader.read_string()?;
-            let ref_pos =
-                Vector3::new((reader.read_f32()?, reader.read_f32()?, reader.read_f32()?));
-
-            let data = RewindableData::read_from(&mut reader)?;
-
-            let rewindable = Rewindable {
-                game_object_name,
-                type_name,
-                ref_pos,
-                data,
-            };
+        self.decode_replay_buffer_with(&ParseOptions::default())
+    }
 
-            rewindables.push(rewindable);
-        }
+    /// Decodes the raw `replay_buffer` within a [`Replay`] to a [`ReplayBuffer`]
+    /// while enforcing the limits in `options`.  
+    pub fn decode_replay_buffer_with(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<ReplayBuffer, ReplayError> {
+        ReplayBuffer::read_from(self.data.replay_buffer.as_slice(), options)
+    }
 
-        Ok(ReplayBuffer {
-            header,
-            rewindable_count,
-            rewindables,
-        })
+    /// Encodes `buffer` and stores it as the raw `replay_buffer` within the [`Replay`].  
+    ///
+    /// See [`ReplayBuffer::encode`] for details.  
+    pub fn encode_replay_buffer(&mut self, buffer: &ReplayBuffer) -> Result<(), ReplayError> {
+        self.data.replay_buffer = buffer.encode()?;
+        Ok(())
     }
 }
 
@@ -65,23 +53,95 @@ pub struct ReplayHeader {
 }
 
 impl ReplayBuffer {
+    /// Decodes a raw `replay_buffer` straight from `reader`.  
+    ///
+    /// The data is inflated while it's being decoded,  
+    /// so the full decompressed buffer is never held in memory.  
+    pub fn read_from<R: Read>(reader: R, options: &ParseOptions) -> Result<Self, ReplayError> {
+        let mut buffer = BufferReader::new(reader, options)?;
+        let mut reader = BinaryReader::new(&mut buffer.inflater);
+
+        let mut read_rewindables = || -> Result<_, ReplayError> {
+            let rewindable_count = options
+                .read_count(&mut reader, Limit::Rewindables)
+                .map_err(|e| e.at(reader.num_bytes_read()))?;
+            let mut rewindables = preallocate(rewindable_count);
+
+            for _ in 0..rewindable_count {
+                let rewindable = Rewindable::read_from(&mut reader, options)
+                    .map_err(|e| e.at(reader.num_bytes_read()))?;
+
+                rewindables.push(rewindable);
+            }
+
+            Ok(rewindables)
+        };
+        let rewindables = read_rewindables().map_err(|e| buffer.inflate_error(e))?;
+        let header = buffer.header;
+
+        Ok(ReplayBuffer {
+            header,
+            rewindable_count: rewindables.len() as i32,
+            rewindables,
+        })
+    }
+
+    /// Encodes the [`ReplayBuffer`] back into a raw `replay_buffer`.  
+    ///
+    /// This is the inverse of [`decode_replay_buffer`](Replay::decode_replay_buffer),  
+    /// the header is written as is and all [`Rewindable`]'s are compressed with DEFLATE.  
+    ///
+    /// Only the [`Rewindable`]'s still within the buffer are written,  
+    /// so any that has been removed via [`get_marble`](Self::get_marble) and friends are left out.  
+    pub fn encode(&self) -> Result<Vec<u8>, ReplayError> {
+        let mut data = Vec::new();
+        let mut writer = BinaryWriter::new(&mut data);
+
+        writer.write_i32(self.rewindables.len() as i32)?;
+        for rewindable in &self.rewindables {
+            rewindable.write_to(&mut writer)?;
+        }
+
+        let compressed_data = compress_to_vec(&data, CompressionLevel::DefaultLevel as u8);
+
+        let mut buffer = Vec::with_capacity(compressed_data.len() + 8);
+        let mut writer = BinaryWriter::new(&mut buffer);
+        writer.write_i32(self.header.session)?;
+        writer.write_i32(self.header.version)?;
+        writer.write_bytes(&compressed_data)?;
+
+        Ok(buffer)
+    }
+
     /// The `type_name` of the [`Rewindable`] that is the player marble object
-    pub(crate) const MARBLE_CONTROLLER: &'static str = "MarbleController";
+    pub(crate) const MARBLE_CONTROLLER: &'static str = <Marble as ReplayObject>::TYPE_NAME;
+
+    /// The `game_object_name` of a marble replaying another run, see [`local_marble`](Self::local_marble)
+    pub(crate) const GHOST: &'static str = "Ghost";
 
     /// The `type_name` of the [`Rewindable`] that is any powerup
-    pub(crate) const POWERUP: &'static str = "Powerup";
+    pub(crate) const POWERUP: &'static str = <Powerup as ReplayObject>::TYPE_NAME;
 
     /// The `type_name` of the [`Rewindable`] that is bumpers
-    pub(crate) const BUMPER_CONTROLLER: &'static str = "BumperController";
+    pub(crate) const BUMPER_CONTROLLER: &'static str = <Bumper as ReplayObject>::TYPE_NAME;
 
     /// The `type_name` of the [`Rewindable`] that is elevator movers
-    pub(crate) const ELEVATOR_MOVER: &'static str = "ElevatorMover";
+    pub(crate) const ELEVATOR_MOVER: &'static str = <Elevator as ReplayObject>::TYPE_NAME;
+
+    /// The `type_name` of the [`Rewindable`] that is gems
+    pub(crate) const GEM_PICKUP: &'static str = <Gem as ReplayObject>::TYPE_NAME;
 
-    /// Get's the [`Rewindable`] with the type name of [`MARBLE_CONTROLLER`](Self::MARBLE_CONTROLLER).  
+    /// The `type_name` of the [`Rewindable`] that is checkpoints
+    pub(crate) const CHECKPOINT: &'static str = <Checkpoint as ReplayObject>::TYPE_NAME;
+
+    /// Get's the first [`Rewindable`] with the type name of [`MARBLE_CONTROLLER`](Self::MARBLE_CONTROLLER).  
     ///
     /// Which is the actual player object within the replay.  
+    /// Replays with ghosts or multiple players have more than one, see [`get_marbles`](Self::get_marbles)
+    /// & [`local_marble`](Self::local_marble).  
     ///
     /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`marble`](Self::marble) to borrow it instead.  
     pub fn get_marble(&mut self) -> Result<Marble, ReplayError> {
         let idx = self
             .rewindables
@@ -94,75 +154,199 @@ impl ReplayBuffer {
         })
     }
 
+    /// Get's all [`Rewindable`]'s with a type name of [`MARBLE_CONTROLLER`](Self::MARBLE_CONTROLLER)
+    ///
+    /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`marbles`](Self::marbles) to borrow them instead.  
+    pub fn get_marbles(&mut self) -> Result<Vec<Marble>, ReplayError> {
+        Ok(self.take_objects())
+    }
+
     /// Get's all [`Rewindable`]'s with a type name of [`POWERUP`](Self::POWERUP)
     ///
     /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`powerups`](Self::powerups) to borrow them instead.  
     pub fn get_powerups(&mut self) -> Result<Vec<Powerup>, ReplayError> {
-        let mut powerups = Vec::new();
+        Ok(self.take_objects())
+    }
 
-        let mut i = 0;
-        loop {
-            if i == self.rewindables.len() {
-                break;
-            }
+    /// Get's all [`Rewindable`]'s with a type name of [`BUMPER_CONTROLLER`](Self::BUMPER_CONTROLLER)
+    ///
+    /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`bumpers`](Self::bumpers) to borrow them instead.  
+    pub fn get_bumpers(&mut self) -> Result<Vec<Bumper>, ReplayError> {
+        Ok(self.take_objects())
+    }
 
-            if &self.rewindables[i].type_name == Self::POWERUP {
-                powerups.push(Powerup {
-                    inner: self.rewindables.remove(i),
-                });
-            } else {
-                i += 1;
-            }
-        }
+    /// Get's all [`Rewindable`]'s with a type name of [`ELEVATOR_MOVER`](Self::ELEVATOR_MOVER)
+    ///
+    /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`elevators`](Self::elevators) to borrow them instead.  
+    pub fn get_elevators(&mut self) -> Result<Vec<Elevator>, ReplayError> {
+        Ok(self.take_objects())
+    }
 
-        Ok(powerups)
+    /// Get's all [`Rewindable`]'s with a type name of [`GEM_PICKUP`](Self::GEM_PICKUP)
+    ///
+    /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
+    /// See [`gems`](Self::gems) to borrow them instead.  
+    pub fn get_gems(&mut self) -> Result<Vec<Gem>, ReplayError> {
+        Ok(self.take_objects())
     }
 
-    /// Get's all [`Rewindable`]'s with a type name of [`BUMPER_CONTROLLER`](Self::BUMPER_CONTROLLER)
+    /// Get's all [`Rewindable`]'s with a type name of [`CHECKPOINT`](Self::CHECKPOINT)
     ///
     /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
-    pub fn get_bumpers(&mut self) -> Result<Vec<Bumper>, ReplayError> {
-        let mut bumpers = Vec::new();
+    /// See [`checkpoints`](Self::checkpoints) to borrow them instead.  
+    pub fn get_checkpoints(&mut self) -> Result<Vec<Checkpoint>, ReplayError> {
+        Ok(self.take_objects())
+    }
 
-        let mut i = 0;
-        loop {
-            if i == self.rewindables.len() {
-                break;
-            }
+    /// Borrows the first [`Rewindable`] with the type name of [`MARBLE_CONTROLLER`](Self::MARBLE_CONTROLLER),
+    /// without removing it from the buffer.
+    pub fn marble(&self) -> Result<Marble<&Rewindable>, ReplayError> {
+        self.objects().next().ok_or(ReplayError::NoMarbleController)
+    }
 
-            if &self.rewindables[i].type_name == Self::BUMPER_CONTROLLER {
-                bumpers.push(Bumper {
-                    inner: self.rewindables.remove(i),
-                });
-            } else {
-                i += 1;
-            }
-        }
+    /// Borrows all [`Rewindable`]'s with a type name of [`MARBLE_CONTROLLER`](Self::MARBLE_CONTROLLER),
+    /// each one's `game_object_name` tells them apart.
+    pub fn marbles(&self) -> impl Iterator<Item = Marble<&Rewindable>> {
+        self.objects()
+    }
 
-        Ok(bumpers)
+    /// Borrows the marble with a matching `game_object_name`.
+    pub fn marble_named(&self, game_object_name: &str) -> Result<Marble<&Rewindable>, ReplayError> {
+        self.marbles()
+            .find(|m| m.game_object_name == game_object_name)
+            .ok_or_else(|| ReplayError::NoMarbleNamed(game_object_name.to_owned()))
     }
 
-    /// Get's all [`Rewindable`]'s with a type name of [`ELEVATOR_MOVER`](Self::ELEVATOR_MOVER)
+    /// Borrows the local player's marble.
     ///
-    /// This operation **removes** the [`Rewindable`] from the buffer and returns it.  
-    pub fn get_elevators(&mut self) -> Result<Vec<Elevator>, ReplayError> {
-        let mut elevators = Vec::new();
+    /// The buffer doesn't mark which marble belongs to the player,
+    /// so with more than one, every marble named [`GHOST`](Self::GHOST) is ruled out.  
+    /// If that still leaves more or less than one, it fails with [`ReplayError::AmbiguousMarble`] listing every `game_object_name`,
+    /// pick the right one with [`marble_named`](Self::marble_named).
+    pub fn local_marble(&self) -> Result<Marble<&Rewindable>, ReplayError> {
+        let mut marbles = self.marbles();
+        let marble = marbles.next().ok_or(ReplayError::NoMarbleController)?;
+        if marbles.next().is_none() {
+            return Ok(marble);
+        }
 
-        let mut i = 0;
-        loop {
-            if i == self.rewindables.len() {
-                break;
-            }
+        let mut players = self.marbles().filter(|m| m.game_object_name != Self::GHOST);
+        if let (Some(player), None) = (players.next(), players.next()) {
+            return Ok(player);
+        }
+
+        Err(ReplayError::AmbiguousMarble(
+            self.marbles().map(|m| m.game_object_name.clone()).collect(),
+        ))
+    }
 
-            if &self.rewindables[i].type_name == Self::ELEVATOR_MOVER {
-                elevators.push(Elevator {
-                    inner: self.rewindables.remove(i),
-                });
-            } else {
-                i += 1;
+    /// Borrows all [`Rewindable`]'s with a type name of [`POWERUP`](Self::POWERUP)
+    pub fn powerups(&self) -> impl Iterator<Item = Powerup<&Rewindable>> {
+        self.objects()
+    }
+
+    /// Borrows all [`Rewindable`]'s with a type name of [`BUMPER_CONTROLLER`](Self::BUMPER_CONTROLLER)
+    pub fn bumpers(&self) -> impl Iterator<Item = Bumper<&Rewindable>> {
+        self.objects()
+    }
+
+    /// Borrows all [`Rewindable`]'s with a type name of [`ELEVATOR_MOVER`](Self::ELEVATOR_MOVER)
+    pub fn elevators(&self) -> impl Iterator<Item = Elevator<&Rewindable>> {
+        self.objects()
+    }
+
+    /// Borrows all [`Rewindable`]'s with a type name of [`GEM_PICKUP`](Self::GEM_PICKUP)
+    pub fn gems(&self) -> impl Iterator<Item = Gem<&Rewindable>> {
+        self.objects()
+    }
+
+    /// Borrows all [`Rewindable`]'s with a type name of [`CHECKPOINT`](Self::CHECKPOINT)
+    pub fn checkpoints(&self) -> impl Iterator<Item = Checkpoint<&Rewindable>> {
+        self.objects()
+    }
+
+    /// Splits the buffer into [`ReplayObjects`] in a single pass, keeping the order of the [`Rewindable`]'s.
+    pub fn into_objects(self) -> ReplayObjects {
+        let mut objects = ReplayObjects {
+            header: self.header,
+            marbles: Vec::new(),
+            powerups: Vec::new(),
+            bumpers: Vec::new(),
+            elevators: Vec::new(),
+            gems: Vec::new(),
+            checkpoints: Vec::new(),
+            others: Vec::new(),
+        };
+
+        for inner in self.rewindables {
+            match inner.type_name.as_str() {
+                Self::MARBLE_CONTROLLER => objects.marbles.push(Marble { inner }),
+                Self::POWERUP => objects.powerups.push(Powerup { inner }),
+                Self::BUMPER_CONTROLLER => objects.bumpers.push(Bumper { inner }),
+                Self::ELEVATOR_MOVER => objects.elevators.push(Elevator { inner }),
+                Self::GEM_PICKUP => objects.gems.push(Gem { inner }),
+                Self::CHECKPOINT => objects.checkpoints.push(Checkpoint { inner }),
+                _ => objects.others.push(GenericObject { inner }),
             }
         }
 
-        Ok(elevators)
+        objects
+    }
+
+    /// Borrows all [`Rewindable`]'s with the [`TYPE_NAME`](ReplayObject::TYPE_NAME) of `T`.
+    ///
+    /// ```ignore
+    /// let powerups = buffer.objects::<Powerup<_>>().collect::<Vec<_>>();
+    /// ```
+    pub fn objects<'a, T: ReplayObject<&'a Rewindable>>(&'a self) -> impl Iterator<Item = T> {
+        self.rewindables
+            .iter()
+            .filter(|r| r.type_name == T::TYPE_NAME)
+            .map(T::from_rewindable_unchecked)
     }
+
+    /// Borrows all [`Rewindable`]'s with a matching `type_name` as a [`GenericObject`],
+    /// for types without a typed wrapper.
+    pub fn generic_objects<'a>(
+        &'a self,
+        type_name: &'a str,
+    ) -> impl Iterator<Item = GenericObject<&'a Rewindable>> {
+        self.rewindables
+            .iter()
+            .filter(move |r| r.type_name == type_name)
+            .map(GenericObject::new)
+    }
+
+    /// Removes all [`Rewindable`]'s with the [`TYPE_NAME`](ReplayObject::TYPE_NAME) of `T` from the buffer,
+    /// keeping the order of the rest.
+    pub fn take_objects<T: ReplayObject>(&mut self) -> Vec<T> {
+        let (taken, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.rewindables)
+            .into_iter()
+            .partition(|r| r.type_name == T::TYPE_NAME);
+        self.rewindables = rest;
+
+        taken
+            .into_iter()
+            .map(T::from_rewindable_unchecked)
+            .collect()
+    }
+}
+
+/// All [`Rewindable`]'s of a [`ReplayBuffer`] split up by their type, see [`ReplayBuffer::into_objects`].
+#[derive(Debug, Clone)]
+pub struct ReplayObjects {
+    pub header: ReplayHeader,
+    /// Every `MarbleController`, the first being the one [`get_marble`](ReplayBuffer::get_marble) returns.
+    pub marbles: Vec<Marble>,
+    pub powerups: Vec<Powerup>,
+    pub bumpers: Vec<Bumper>,
+    pub elevators: Vec<Elevator>,
+    pub gems: Vec<Gem>,
+    pub checkpoints: Vec<Checkpoint>,
+    /// Every [`Rewindable`] without a typed wrapper.
+    pub others: Vec<GenericObject>,
 }
@@ -0,0 +1,280 @@
+use std::{io::Read, ops::Range};
+
+use csharp_binary_encoding::BinaryReader;
+
+use crate::{
+    CurveValue, Marble, ParseOptions, Replay, ReplayBuffer, ReplayError, ReplayHeader,
+    RewindCurveType, Rewindable, RewindableData, Vector3,
+    buffer_reader::{BufferReader, RewindableHeader, read_at, skip},
+    parse_options::{Limit, preallocate},
+};
+
+/// An index over a decompressed `replay_buffer` that knows where every [`Rewindable`] and field is,
+/// without having decoded any of the curves.
+///
+/// Individual rewindables or fields can then be decoded on demand,
+/// which is a lot cheaper than a full [`decode_replay_buffer`](Replay::decode_replay_buffer) when only a few are needed.
+#[derive(Debug, Clone)]
+pub struct ReplayBufferIndex {
+    pub header: ReplayHeader,
+    pub rewindables: Vec<RewindableEntry>,
+    data: Vec<u8>,
+    options: ParseOptions,
+}
+
+/// The location of a single [`Rewindable`] within a [`ReplayBufferIndex`]
+#[derive(Debug, Clone)]
+pub struct RewindableEntry {
+    pub game_object_name: String,
+    pub type_name: String,
+    pub ref_pos: Vector3,
+    /// The byte range of the whole rewindable within the decompressed `replay_buffer`.
+    pub range: Range<usize>,
+    pub fields: Vec<FieldEntry>,
+}
+
+/// The location of a single [`RewindableData`] within a [`ReplayBufferIndex`]
+#[derive(Debug, Clone)]
+pub struct FieldEntry {
+    pub index: i32,
+    pub text: String,
+    pub rewind_type: RewindCurveType,
+    /// The byte range of the whole field, including its `index` & `text`, within the decompressed `replay_buffer`.
+    pub range: Range<usize>,
+}
+
+impl RewindableEntry {
+    /// Finds the field with the matching [`text`](FieldEntry::text).
+    pub fn field(&self, text: &str) -> Option<&FieldEntry> {
+        self.fields.iter().find(|f| f.text == text)
+    }
+}
+
+impl Replay {
+    /// Builds a [`ReplayBufferIndex`] over the raw `replay_buffer` within a [`Replay`].
+    pub fn index_replay_buffer(&self) -> Result<ReplayBufferIndex, ReplayError> {
+        self.index_replay_buffer_with(&ParseOptions::default())
+    }
+
+    /// Builds a [`ReplayBufferIndex`] over the raw `replay_buffer` within a [`Replay`]
+    /// while enforcing the limits in `options`.
+    pub fn index_replay_buffer_with(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<ReplayBufferIndex, ReplayError> {
+        ReplayBufferIndex::read_from(self.data.replay_buffer.as_slice(), options)
+    }
+}
+
+impl ReplayBufferIndex {
+    /// Decompresses a raw `replay_buffer` from `reader` and indexes it.
+    pub fn read_from<R: Read>(reader: R, options: &ParseOptions) -> Result<Self, ReplayError> {
+        let (header, data, error) = BufferReader::new(reader, options)?.read_to_end();
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let mut pos = 0;
+        let rewindable_count = read_at(&data, &mut pos, |reader| {
+            options.read_count(reader, Limit::Rewindables)
+        })?;
+
+        let mut rewindables = preallocate(rewindable_count);
+        for _ in 0..rewindable_count {
+            rewindables.push(index_rewindable(&data, &mut pos, options)?);
+        }
+
+        Ok(Self {
+            header,
+            rewindables,
+            data,
+            options: *options,
+        })
+    }
+
+    /// The decompressed `replay_buffer` that all ranges point into.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns all entries with a matching `type_name`.
+    pub fn entries_of_type<'a>(
+        &'a self,
+        type_name: &'a str,
+    ) -> impl Iterator<Item = &'a RewindableEntry> + 'a {
+        self.rewindables
+            .iter()
+            .filter(move |r| r.type_name == type_name)
+    }
+
+    /// Decodes a single [`Rewindable`] with all of its fields.
+    pub fn decode_rewindable(&self, entry: &RewindableEntry) -> Result<Rewindable, ReplayError> {
+        let mut reader = BinaryReader::new(&self.data[entry.range.clone()]);
+        Rewindable::read_from(&mut reader, &self.options).map_err(|e| {
+            e.at(reader.num_bytes_read())
+                .offset_by(entry.range.start as u64)
+        })
+    }
+
+    /// Decodes a single field of a [`Rewindable`].
+    pub fn decode_field(
+        &self,
+        entry: &RewindableEntry,
+        field: &FieldEntry,
+    ) -> Result<RewindableData, ReplayError> {
+        let mut reader = BinaryReader::new(&self.data[field.range.clone()]);
+        RewindableData::read_one(&mut reader, &self.options).map_err(|e| {
+            e.with_context(reader.num_bytes_read(), |c| {
+                c.game_object_name = Some(entry.game_object_name.clone());
+                c.type_name = Some(entry.type_name.clone());
+            })
+            .offset_by(field.range.start as u64)
+        })
+    }
+
+    /// Decodes the first `MarbleController`, without touching any other [`Rewindable`].
+    pub fn get_marble(&self) -> Result<Marble, ReplayError> {
+        let entry = self
+            .entries_of_type(ReplayBuffer::MARBLE_CONTROLLER)
+            .next()
+            .ok_or(ReplayError::NoMarbleController)?;
+
+        Ok(Marble {
+            inner: self.decode_rewindable(entry)?,
+        })
+    }
+
+    /// Decodes the `MarbleController` with a matching `game_object_name`, without touching any other [`Rewindable`].
+    pub fn get_marble_named(&self, game_object_name: &str) -> Result<Marble, ReplayError> {
+        let entry = self
+            .entries_of_type(ReplayBuffer::MARBLE_CONTROLLER)
+            .find(|e| e.game_object_name == game_object_name)
+            .ok_or_else(|| ReplayError::NoMarbleNamed(game_object_name.to_owned()))?;
+
+        Ok(Marble {
+            inner: self.decode_rewindable(entry)?,
+        })
+    }
+
+    /// Decodes every [`Rewindable`], the same as [`decode_replay_buffer`](Replay::decode_replay_buffer).
+    pub fn decode_all(&self) -> Result<ReplayBuffer, ReplayError> {
+        let rewindables = self
+            .rewindables
+            .iter()
+            .map(|entry| self.decode_rewindable(entry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ReplayBuffer {
+            header: self.header.clone(),
+            rewindable_count: rewindables.len() as i32,
+            rewindables,
+        })
+    }
+}
+
+fn index_rewindable(
+    data: &[u8],
+    pos: &mut usize,
+    options: &ParseOptions,
+) -> Result<RewindableEntry, ReplayError> {
+    let start = *pos;
+    let (header, num) = read_at(data, pos, |reader| {
+        Ok((
+            RewindableHeader::read_from(reader, options)?,
+            options.read_count(reader, Limit::Fields)?,
+        ))
+    })?;
+
+    let mut index_fields = || -> Result<_, ReplayError> {
+        let mut fields = preallocate(num);
+        for _ in 0..num {
+            fields.push(index_field(data, pos, options)?);
+        }
+        Ok(fields)
+    };
+    let fields = index_fields().map_err(|e| header.context(e, *pos as u64))?;
+
+    Ok(RewindableEntry {
+        game_object_name: header.game_object_name,
+        type_name: header.type_name,
+        ref_pos: header.ref_pos,
+        range: start..*pos,
+        fields,
+    })
+}
+
+fn index_field(
+    data: &[u8],
+    pos: &mut usize,
+    options: &ParseOptions,
+) -> Result<FieldEntry, ReplayError> {
+    let start = *pos;
+    let (index, text) = read_at(data, pos, |reader| {
+        Ok((reader.read_i32()?, options.read_string(reader)?))
+    })?;
+
+    let mut skip_field = || -> Result<_, ReplayError> {
+        let rewind_type = read_at(data, pos, RewindCurveType::read_type)?;
+        match rewind_type {
+            RewindCurveType::UInt32Array | RewindCurveType::Int32Array => {
+                let count = read_at(data, pos, |reader| {
+                    expect_type(reader, rewind_type)?;
+                    reader.read_boolean()?;
+                    options.read_count(reader, Limit::ArrayLength)
+                })?;
+                for _ in 0..count {
+                    skip_fitter(
+                        data,
+                        pos,
+                        rewind_type.get_curve_type_for_array_type(),
+                        options,
+                    )?;
+                }
+            }
+            _ => skip_fitter(data, pos, rewind_type, options)?,
+        }
+        Ok(rewind_type)
+    };
+    let rewind_type =
+        skip_field().map_err(|e| e.with_context(*pos as u64, |c| c.field = Some(text.clone())))?;
+
+    Ok(FieldEntry {
+        index,
+        text,
+        rewind_type,
+        range: start..*pos,
+    })
+}
+
+/// Skips over a [`RewindCurveFitter`](crate::RewindCurveFitter) using only its sample count.
+fn skip_fitter(
+    data: &[u8],
+    pos: &mut usize,
+    curve_type: RewindCurveType,
+    options: &ParseOptions,
+) -> Result<(), ReplayError> {
+    let count = read_at(data, pos, |reader| {
+        expect_type(reader, curve_type)?;
+        reader.read_boolean()?;
+        let count = options.read_count(reader, Limit::SamplesPerCurve)?;
+        reader.read_boolean()?;
+        Ok(count)
+    })?;
+
+    skip(data, pos, count * (f32::SIZE + curve_type.size()))
+}
+
+fn expect_type<R: Read>(
+    reader: &mut BinaryReader<R>,
+    curve_type: RewindCurveType,
+) -> Result<(), ReplayError> {
+    let _type = RewindCurveType::read_type(reader)?;
+    if _type != curve_type {
+        return Err(ReplayError::MismatchedCurveTypes {
+            lhs: _type,
+            rhs: curve_type,
+        });
+    }
+
+    Ok(())
+}
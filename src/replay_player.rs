@@ -0,0 +1,301 @@
+use crate::{
+    CurveCursor, CurveValue, Quaternion, ReplayBuffer, Vector3,
+    rewind_curve::{IRewindCurve, merge_ranges},
+};
+
+/// Plays back a [`ReplayBuffer`] and produces a [`Snapshot`] of the world at any point in time.
+///
+/// Every tracked field keeps its own [`CurveCursor`],
+/// so stepping forward or backward in small increments stays cheap.
+///
+/// The buffer is only borrowed, unlike [`get_marble`](ReplayBuffer::get_marble) and friends nothing is removed from it.
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer<'a> {
+    time: f32,
+    start_time: f32,
+    end_time: f32,
+    marbles: Vec<MarbleTrack<'a>>,
+    powerups: Vec<PowerupTrack<'a>>,
+    bumpers: Vec<BumperTrack<'a>>,
+    elevators: Vec<ElevatorTrack<'a>>,
+}
+
+/// The state of the world at a single point in time, see [`ReplayPlayer`].
+///
+/// Fields that are missing from the replay are [`None`].
+#[derive(Debug, Clone)]
+pub struct Snapshot<'a> {
+    pub time: f32,
+    /// Every `MarbleController` in buffer order, ghosts included, see [`ReplayBuffer::marbles`].
+    pub marbles: Vec<MarbleState<'a>>,
+    pub powerups: Vec<PowerupState<'a>>,
+    pub bumpers: Vec<BumperState<'a>>,
+    pub elevators: Vec<ElevatorState<'a>>,
+}
+
+/// The state of a single `MarbleController` within a [`Snapshot`]
+#[derive(Debug, Clone)]
+pub struct MarbleState<'a> {
+    pub game_object_name: &'a str,
+    pub position: Option<Vector3>,
+    pub velocity: Option<Vector3>,
+    pub omega: Option<Vector3>,
+    pub qw: Option<Quaternion>,
+    pub gravity_quat: Option<Quaternion>,
+    pub mega_marble_size_scale: Option<f32>,
+    pub collected_gems: Option<u16>,
+}
+
+/// The state of a single `Powerup` within a [`Snapshot`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerupState<'a> {
+    pub game_object_name: &'a str,
+    pub available_for_pickup: Option<bool>,
+}
+
+/// The state of a single `BumperController` within a [`Snapshot`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BumperState<'a> {
+    pub game_object_name: &'a str,
+    pub strike_time_left: Option<f32>,
+}
+
+/// The state of a single `ElevatorMover` within a [`Snapshot`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElevatorState<'a> {
+    pub game_object_name: &'a str,
+    pub t: Option<i32>,
+    pub collapsing: Option<bool>,
+    pub stop_time: Option<f32>,
+    pub enable_bob: Option<bool>,
+    pub global_time: Option<i32>,
+}
+
+/// Creates a [`CurveCursor`] for the field named `$text`, if it exists with the right curve type.
+macro_rules! cursor {
+    ($rewindable:expr, $text:expr, $rewind_type:ident) => {
+        $rewindable.find($text).and_then(|d| match &d.curve {
+            IRewindCurve::$rewind_type(fitter) => Some(fitter.cursor()),
+            _ => None,
+        })
+    };
+}
+
+#[derive(Debug, Clone)]
+struct MarbleTrack<'a> {
+    game_object_name: &'a str,
+    position: Option<CurveCursor<'a, Vector3>>,
+    velocity: Option<CurveCursor<'a, Vector3>>,
+    omega: Option<CurveCursor<'a, Vector3>>,
+    qw: Option<CurveCursor<'a, Quaternion>>,
+    gravity_quat: Option<CurveCursor<'a, Quaternion>>,
+    mega_marble_size_scale: Option<CurveCursor<'a, f32>>,
+    collected_gems: Option<CurveCursor<'a, u16>>,
+}
+
+#[derive(Debug, Clone)]
+struct PowerupTrack<'a> {
+    game_object_name: &'a str,
+    available_for_pickup: Option<CurveCursor<'a, bool>>,
+}
+
+#[derive(Debug, Clone)]
+struct BumperTrack<'a> {
+    game_object_name: &'a str,
+    strike_time_left: Option<CurveCursor<'a, f32>>,
+}
+
+#[derive(Debug, Clone)]
+struct ElevatorTrack<'a> {
+    game_object_name: &'a str,
+    t: Option<CurveCursor<'a, i32>>,
+    collapsing: Option<CurveCursor<'a, bool>>,
+    stop_time: Option<CurveCursor<'a, f32>>,
+    enable_bob: Option<CurveCursor<'a, bool>>,
+    global_time: Option<CurveCursor<'a, i32>>,
+}
+
+/// Samples an optional cursor at `time`.
+fn sample<T: CurveValue>(cursor: &mut Option<CurveCursor<'_, T>>, time: f32) -> Option<T> {
+    cursor.as_mut().and_then(|cursor| cursor.sample(time))
+}
+
+impl<'a> ReplayPlayer<'a> {
+    /// Creates a player over `buffer`, positioned at the start of the replay.
+    pub fn new(buffer: &'a ReplayBuffer) -> Self {
+        let of_type = |type_name: &'static str| {
+            buffer
+                .rewindables
+                .iter()
+                .filter(move |r| r.type_name == type_name)
+        };
+
+        let marbles = of_type(ReplayBuffer::MARBLE_CONTROLLER)
+            .map(|r| MarbleTrack {
+                game_object_name: &r.game_object_name,
+                position: cursor!(r, "Position", Vector3),
+                velocity: cursor!(r, "Velocity", Vector3),
+                omega: cursor!(r, "Omega", Vector3),
+                qw: cursor!(r, "qW", Quaternion),
+                gravity_quat: cursor!(r, "GravityQuat", Quaternion),
+                mega_marble_size_scale: cursor!(r, "MegaMarbleSizeScale", Float),
+                collected_gems: cursor!(r, "CollectedGems", UShort),
+            })
+            .collect();
+        let powerups = of_type(ReplayBuffer::POWERUP)
+            .map(|r| PowerupTrack {
+                game_object_name: &r.game_object_name,
+                available_for_pickup: cursor!(r, "AvailableForPickup", Bool),
+            })
+            .collect();
+        let bumpers = of_type(ReplayBuffer::BUMPER_CONTROLLER)
+            .map(|r| BumperTrack {
+                game_object_name: &r.game_object_name,
+                strike_time_left: cursor!(r, "StrikeTimeLeft", Float),
+            })
+            .collect();
+        let elevators = of_type(ReplayBuffer::ELEVATOR_MOVER)
+            .map(|r| ElevatorTrack {
+                game_object_name: &r.game_object_name,
+                t: cursor!(r, "T", Int),
+                collapsing: cursor!(r, "Collapsing", Bool),
+                stop_time: cursor!(r, "StopTime", Float),
+                enable_bob: cursor!(r, "EnableBob", Bool),
+                global_time: cursor!(r, "GlobalTime", Int),
+            })
+            .collect();
+
+        let (start_time, end_time) = merge_ranges(
+            buffer
+                .rewindables
+                .iter()
+                .flat_map(|r| r.data.iter())
+                .map(|d| d.curve.time_range()),
+        )
+        .unwrap_or((0.0, 0.0));
+
+        Self {
+            time: start_time,
+            start_time,
+            end_time,
+            marbles,
+            powerups,
+            bumpers,
+            elevators,
+        }
+    }
+
+    /// The time of the earliest keyframe within the buffer.
+    pub fn start_time(&self) -> f32 {
+        self.start_time
+    }
+
+    /// The time of the latest keyframe within the buffer.
+    pub fn end_time(&self) -> f32 {
+        self.end_time
+    }
+
+    /// The current playback time.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Moves playback to `time` and returns the [`Snapshot`] there.
+    pub fn seek(&mut self, time: f32) -> Snapshot<'a> {
+        self.time = time;
+        self.snapshot()
+    }
+
+    /// Moves playback by `dt` seconds, which may be negative, and returns the [`Snapshot`] there.
+    pub fn step(&mut self, dt: f32) -> Snapshot<'a> {
+        self.seek(self.time + dt)
+    }
+
+    /// Returns the [`Snapshot`] at the current playback time.
+    pub fn snapshot(&mut self) -> Snapshot<'a> {
+        let time = self.time;
+
+        Snapshot {
+            time,
+            marbles: self
+                .marbles
+                .iter_mut()
+                .map(|m| MarbleState {
+                    game_object_name: m.game_object_name,
+                    position: sample(&mut m.position, time),
+                    velocity: sample(&mut m.velocity, time),
+                    omega: sample(&mut m.omega, time),
+                    qw: sample(&mut m.qw, time),
+                    gravity_quat: sample(&mut m.gravity_quat, time),
+                    mega_marble_size_scale: sample(&mut m.mega_marble_size_scale, time),
+                    collected_gems: sample(&mut m.collected_gems, time),
+                })
+                .collect(),
+            powerups: self
+                .powerups
+                .iter_mut()
+                .map(|p| PowerupState {
+                    game_object_name: p.game_object_name,
+                    available_for_pickup: sample(&mut p.available_for_pickup, time),
+                })
+                .collect(),
+            bumpers: self
+                .bumpers
+                .iter_mut()
+                .map(|b| BumperState {
+                    game_object_name: b.game_object_name,
+                    strike_time_left: sample(&mut b.strike_time_left, time),
+                })
+                .collect(),
+            elevators: self
+                .elevators
+                .iter_mut()
+                .map(|e| ElevatorState {
+                    game_object_name: e.game_object_name,
+                    t: sample(&mut e.t, time),
+                    collapsing: sample(&mut e.collapsing, time),
+                    stop_time: sample(&mut e.stop_time, time),
+                    enable_bob: sample(&mut e.enable_bob, time),
+                    global_time: sample(&mut e.global_time, time),
+                })
+                .collect(),
+        }
+    }
+
+    /// Iterates from the current time up to & including [`end_time`](Self::end_time) at `rate` frames per second.
+    ///
+    /// Frame times are computed from the starting time, so they don't drift over long replays.
+    pub fn frames(&mut self, rate: f32) -> Frames<'_, 'a> {
+        Frames {
+            start: self.time,
+            interval: 1.0 / rate,
+            frame: 0,
+            player: self,
+        }
+    }
+}
+
+/// An iterator over [`Snapshot`]'s at a fixed rate, see [`ReplayPlayer::frames`].
+#[derive(Debug)]
+pub struct Frames<'p, 'a> {
+    player: &'p mut ReplayPlayer<'a>,
+    start: f32,
+    interval: f32,
+    frame: u32,
+}
+
+impl<'a> Iterator for Frames<'_, 'a> {
+    type Item = Snapshot<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let time = self.start + self.interval * self.frame as f32;
+        let valid = self.interval.is_finite() && self.interval > 0.0;
+        let in_range = time <= self.player.end_time;
+        if !valid || !in_range {
+            return None;
+        }
+
+        self.frame += 1;
+        Some(self.player.seek(time))
+    }
+}
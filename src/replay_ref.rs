@@ -0,0 +1,116 @@
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ParseOptions, Replay, ReplayBuffer, ReplayCosmetics, ReplayData, ReplayError,
+    replay::serialize_bin,
+};
+
+/// A borrowed [`Replay`] that points into the raw `.replay` file instead of copying out of it.
+///
+/// Useful when only the metadata is needed, since parsing one doesn't allocate.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct ReplayRef<'a> {
+    #[serde(rename = "typeId")]
+    pub type_id: i32,
+    pub version: i32,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: &'a str,
+    #[serde(borrow)]
+    pub data: ReplayDataRef<'a>,
+}
+
+/// Replay specific data within a [`ReplayRef`]
+#[derive(Deserialize, Serialize, Clone, Copy)]
+pub struct ReplayDataRef<'a> {
+    pub level: &'a str,
+    pub player: &'a str,
+    pub score: f64,
+    #[serde(borrow)]
+    pub cosmetics: ReplayCosmeticsRef<'a>,
+    /// The raw `replay_buffer`, use [`decode_replay_buffer`](ReplayRef::decode_replay_buffer) to decode this data.
+    #[serde(rename = "replayBuffer", serialize_with = "serialize_bin")]
+    pub replay_buffer: &'a [u8],
+}
+
+/// All the different cosmetics within a [`ReplayDataRef`]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct ReplayCosmeticsRef<'a> {
+    pub skin: &'a str,
+    pub trail: &'a str,
+    pub respawn: &'a str,
+    pub hat: &'a str,
+    pub blast: &'a str,
+}
+
+impl Debug for ReplayDataRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[derive(Debug)]
+        #[allow(unused)]
+        struct VisualReplayData<'a> {
+            level: &'a str,
+            player: &'a str,
+            score: f64,
+            cosmetics: &'a ReplayCosmeticsRef<'a>,
+            replay_buffer: usize,
+        }
+
+        let display_info = VisualReplayData {
+            level: self.level,
+            player: self.player,
+            score: self.score,
+            cosmetics: &self.cosmetics,
+            replay_buffer: self.replay_buffer.len(),
+        };
+
+        if f.alternate() {
+            write!(f, "{display_info:#?}")
+        } else {
+            write!(f, "{display_info:?}")
+        }
+    }
+}
+
+impl<'a> ReplayRef<'a> {
+    /// Parses a raw replay file into a [`ReplayRef`] that borrows from `data`.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ReplayError> {
+        Ok(rmp_serde::from_slice(data)?)
+    }
+
+    /// Decodes the borrowed `replay_buffer` to a [`ReplayBuffer`]
+    pub fn decode_replay_buffer(&self) -> Result<ReplayBuffer, ReplayError> {
+        self.decode_replay_buffer_with(&ParseOptions::default())
+    }
+
+    /// Decodes the borrowed `replay_buffer` to a [`ReplayBuffer`]
+    /// while enforcing the limits in `options`.
+    pub fn decode_replay_buffer_with(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<ReplayBuffer, ReplayError> {
+        ReplayBuffer::read_from(self.data.replay_buffer, options)
+    }
+
+    /// Copies all borrowed data into an owned [`Replay`].
+    pub fn into_owned(self) -> Replay {
+        Replay {
+            type_id: self.type_id,
+            version: self.version,
+            updated_at: self.updated_at.to_owned(),
+            data: ReplayData {
+                level: self.data.level.to_owned(),
+                player: self.data.player.to_owned(),
+                score: self.data.score,
+                cosmetics: ReplayCosmetics {
+                    skin: self.data.cosmetics.skin.to_owned(),
+                    trail: self.data.cosmetics.trail.to_owned(),
+                    respawn: self.data.cosmetics.respawn.to_owned(),
+                    hat: self.data.cosmetics.hat.to_owned(),
+                    blast: self.data.cosmetics.blast.to_owned(),
+                },
+                replay_buffer: self.data.replay_buffer.to_owned(),
+            },
+        }
+    }
+}
@@ -1,12 +1,21 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+    sync::Arc,
+};
 
-use csharp_binary_encoding::BinaryReader;
+use csharp_binary_encoding::{BinaryReader, BinaryWriter};
 
 use crate::{
-    Quaternion, ReplayError, RewindCurveFitter, RewindCurveFitterArray, Vector2, Vector3,
-    circular_buffer::CircularBuffer, rewind_curve_type::RewindCurveType,
+    CurveValue, ParseOptions, Quaternion, ReplayError, RewindCurveFitter, RewindCurveFitterArray,
+    Vector2, Vector3,
+    parse_options::{Limit, MAX_PREALLOCATION, preallocate},
+    rewind_curve_type::RewindCurveType,
 };
 
+/// The decoded curve of a [`RewindableData`](crate::RewindableData), one variant per [`RewindCurveType`].
+///
+/// See [`Rewindable::field`](crate::Rewindable::field) for typed access without matching on this.
 #[derive(Debug, Clone)]
 pub enum IRewindCurve {
     Float(RewindCurveFitter<f32>),
@@ -21,44 +30,382 @@ pub enum IRewindCurve {
     Int32Array(RewindCurveFitterArray<i32>),
 }
 
+impl IRewindCurve {
+    /// Returns the [`RewindCurveType`] that matches the inner curve.  
+    pub fn curve_type(&self) -> RewindCurveType {
+        match self {
+            Self::Float(_) => RewindCurveType::Float,
+            Self::Int(_) => RewindCurveType::Int,
+            Self::Bool(_) => RewindCurveType::Bool,
+            Self::Vector2(_) => RewindCurveType::Vector2,
+            Self::Vector3(_) => RewindCurveType::Vector3,
+            Self::Quaternion(_) => RewindCurveType::Quaternion,
+            Self::UShort(_) => RewindCurveType::UShort,
+            Self::UInt32(_) => RewindCurveType::UInt32,
+            Self::UInt32Array(_) => RewindCurveType::UInt32Array,
+            Self::Int32Array(_) => RewindCurveType::Int32Array,
+        }
+    }
+
+    /// Returns the time of the first & last keyframe of the inner curve, or of all curves within an array.
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        match self {
+            Self::Float(fitter) => fitter.recent_curve.time_range(),
+            Self::Int(fitter) => fitter.recent_curve.time_range(),
+            Self::Bool(fitter) => fitter.recent_curve.time_range(),
+            Self::Vector2(fitter) => fitter.recent_curve.time_range(),
+            Self::Vector3(fitter) => fitter.recent_curve.time_range(),
+            Self::Quaternion(fitter) => fitter.recent_curve.time_range(),
+            Self::UShort(fitter) => fitter.recent_curve.time_range(),
+            Self::UInt32(fitter) => fitter.recent_curve.time_range(),
+            Self::UInt32Array(array) => {
+                merge_ranges(array.curves.iter().map(|f| f.recent_curve.time_range()))
+            }
+            Self::Int32Array(array) => {
+                merge_ranges(array.curves.iter().map(|f| f.recent_curve.time_range()))
+            }
+        }
+    }
+
+    /// Calls `f` with the timeline of the inner curve, or of every curve within an array.
+    pub(crate) fn for_each_timeline_mut(&mut self, mut f: impl FnMut(&mut Arc<[f32]>)) {
+        match self {
+            Self::Float(fitter) => f(&mut fitter.recent_curve.times),
+            Self::Int(fitter) => f(&mut fitter.recent_curve.times),
+            Self::Bool(fitter) => f(&mut fitter.recent_curve.times),
+            Self::Vector2(fitter) => f(&mut fitter.recent_curve.times),
+            Self::Vector3(fitter) => f(&mut fitter.recent_curve.times),
+            Self::Quaternion(fitter) => f(&mut fitter.recent_curve.times),
+            Self::UShort(fitter) => f(&mut fitter.recent_curve.times),
+            Self::UInt32(fitter) => f(&mut fitter.recent_curve.times),
+            Self::UInt32Array(array) => array
+                .curves
+                .iter_mut()
+                .for_each(|fitter| f(&mut fitter.recent_curve.times)),
+            Self::Int32Array(array) => array
+                .curves
+                .iter_mut()
+                .for_each(|fitter| f(&mut fitter.recent_curve.times)),
+        }
+    }
+
+    pub(crate) fn write_to<W: Write>(
+        &self,
+        writer: &mut BinaryWriter<W>,
+    ) -> Result<(), ReplayError> {
+        let curve_type = self.curve_type();
+        match self {
+            Self::Float(fitter) => fitter.write_to(writer, curve_type),
+            Self::Int(fitter) => fitter.write_to(writer, curve_type),
+            Self::Bool(fitter) => fitter.write_to(writer, curve_type),
+            Self::Vector2(fitter) => fitter.write_to(writer, curve_type),
+            Self::Vector3(fitter) => fitter.write_to(writer, curve_type),
+            Self::Quaternion(fitter) => fitter.write_to(writer, curve_type),
+            Self::UShort(fitter) => fitter.write_to(writer, curve_type),
+            Self::UInt32(fitter) => fitter.write_to(writer, curve_type),
+            Self::UInt32Array(fitter) => fitter.write_to(writer, curve_type),
+            Self::Int32Array(fitter) => fitter.write_to(writer, curve_type),
+        }
+    }
+}
+
+/// Combines time ranges into one that covers all of them.
+pub(crate) fn merge_ranges(ranges: impl Iterator<Item = Option<(f32, f32)>>) -> Option<(f32, f32)> {
+    ranges
+        .flatten()
+        .reduce(|(start, end), (s, e)| (start.min(s), end.max(e)))
+}
+
+/// How a curve is sampled before its first or after its last keyframe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Extrapolation {
+    /// Holds the first or last value.
+    #[default]
+    Clamp,
+    /// Continues the first or last segment, step curves hold their value instead.
+    Linear,
+    /// Returns [`None`] outside of the curve.
+    Discard,
+}
+
+/// A curve of keyframes, stored as two contiguous slices of equal length.
+///
+/// Parsed curves are always in chronological order, so `times` is sorted.  
+/// Curves on the same [`Rewindable`](crate::Rewindable) that were recorded on the exact same times
+/// share one timeline, see [`shares_timeline`](Self::shares_timeline).
 #[derive(Debug, Clone)]
 pub struct RewindCurve<T: Clone> {
     pub interpolated: bool,
-    pub times: CircularBuffer<f32>,
-    pub values: CircularBuffer<T>,
+    times: Arc<[f32]>,
+    values: Vec<T>,
     // never used in parsed data
     // pub type_cache: Option<RewindCurveType>,
     // pub last_index: Option<i32>,
 }
 
 impl<T: Clone> RewindCurve<T> {
-    pub(crate) unsafe fn read_from(
-        reader: &mut BinaryReader<&[u8]>,
-        curve_type: RewindCurveType,
-    ) -> Result<Self, ReplayError> {
-        let count = reader.read_i32()? as usize;
-        let interpolated = reader.read_boolean()?;
+    /// Creates a curve from keyframes, `times` should be sorted.
+    ///
+    /// # Panics
+    /// If `times` & `values` aren't the same length.
+    pub fn new(interpolated: bool, times: impl Into<Arc<[f32]>>, values: Vec<T>) -> Self {
+        let times = times.into();
+        assert_eq!(
+            times.len(),
+            values.len(),
+            "RewindCurve needs exactly one value per time"
+        );
 
-        let mut times = CircularBuffer::with_capacity(count);
-        for _ in 0..count {
-            times.push_back(reader.read_f32()?);
+        Self {
+            interpolated,
+            times,
+            values,
         }
+    }
 
-        let raw_values = reader.read_bytes(count * curve_type.size())?;
-        let mut values = CircularBuffer::with_capacity(count);
+    /// The time of every keyframe.
+    pub fn times(&self) -> &[f32] {
+        &self.times
+    }
 
-        unsafe {
-            for i in 0..count {
-                let ptr = raw_values.as_ptr().add(i * curve_type.size());
-                let value = std::ptr::read(ptr as *const T);
-                values.push_back(value);
-            }
+    /// The shared timeline of the curve, the same as [`times`](Self::times).
+    pub fn timeline(&self) -> &Arc<[f32]> {
+        &self.times
+    }
+
+    /// Returns `true` if both curves use the very same timeline,
+    /// meaning their keyframes line up & can be iterated together by index.
+    pub fn shares_timeline<U: Clone>(&self, other: &RewindCurve<U>) -> bool {
+        Arc::ptr_eq(&self.times, &other.times)
+    }
+
+    /// The value of every keyframe.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Iterates over every keyframe as `(time, value)`.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (f32, &T)> + ExactSizeIterator {
+        self.times.iter().copied().zip(&self.values)
+    }
+
+    /// The amount of keyframes.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the time of the first & last keyframe, or [`None`] if the curve is empty.
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        Some((*self.times.first()?, *self.times.last()?))
+    }
+}
+
+impl<T: CurveValue> RewindCurve<T> {
+    /// Samples the curve at `time`, clamping to the first & last keyframe.  
+    /// Returns [`None`] if the curve is empty.
+    pub fn sample(&self, time: f32) -> Option<T> {
+        self.sample_with(time, Extrapolation::Clamp)
+    }
+
+    /// Samples the curve at `time` with the given [`Extrapolation`] at the ends.
+    pub fn sample_with(&self, time: f32, extrapolation: Extrapolation) -> Option<T> {
+        self.sample_at(time, self.interpolated, extrapolation)
+    }
+
+    /// Returns the index of the last keyframe at or before `time`.
+    pub fn keyframe_index(&self, time: f32) -> Option<usize> {
+        self.times.partition_point(|&t| t <= time).checked_sub(1)
+    }
+
+    /// Returns a [`CurveCursor`] for sampling the curve at mostly increasing or decreasing times.
+    pub fn cursor(&self) -> CurveCursor<'_, T> {
+        CurveCursor::new(self, self.interpolated)
+    }
+
+    pub(crate) fn sample_at(
+        &self,
+        time: f32,
+        interpolated: bool,
+        extrapolation: Extrapolation,
+    ) -> Option<T> {
+        self.sample_near(time, interpolated, extrapolation, |curve| {
+            curve.keyframe_index(time)
+        })
+    }
+
+    /// Samples the curve using `find` to locate the keyframe at or before `time`.
+    fn sample_near(
+        &self,
+        time: f32,
+        interpolated: bool,
+        extrapolation: Extrapolation,
+        find: impl FnOnce(&Self) -> Option<usize>,
+    ) -> Option<T> {
+        let count = self.values.len();
+        if count == 0 || time.is_nan() {
+            return None;
+        }
+
+        let segment = |idx: usize| {
+            let (from, to) = (self.times[idx], self.times[idx + 1]);
+            let t = if to > from {
+                (time - from) / (to - from)
+            } else {
+                0.0
+            };
+            T::interpolate(&self.values[idx], &self.values[idx + 1], t)
+        };
+
+        let outside = time < self.times[0] || time > self.times[count - 1];
+        if outside {
+            let edge = if time < self.times[0] { 0 } else { count - 1 };
+            return match extrapolation {
+                Extrapolation::Discard => None,
+                Extrapolation::Linear if interpolated && count > 1 => {
+                    Some(segment(edge.min(count - 2)))
+                }
+                _ => Some(self.values[edge].clone()),
+            };
         }
 
+        let idx = find(self)?;
+        if interpolated && idx < count - 1 {
+            Some(segment(idx))
+        } else {
+            Some(self.values[idx].clone())
+        }
+    }
+
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
+    ) -> Result<Self, ReplayError> {
+        let count = options.read_count(reader, Limit::SamplesPerCurve)?;
+        let interpolated = reader.read_boolean()?;
+
+        let times = read_values::<_, f32>(reader, count)?.into();
+        let values = read_values(reader, count)?;
+
         Ok(Self {
             interpolated,
             times,
             values,
         })
     }
+
+    pub(crate) fn write_to<W: Write>(
+        &self,
+        writer: &mut BinaryWriter<W>,
+    ) -> Result<(), ReplayError> {
+        let count = self.values.len();
+        writer.write_i32(count as i32)?;
+        writer.write_boolean(self.interpolated)?;
+
+        for time in self.times.iter() {
+            writer.write_f32(*time)?;
+        }
+
+        let mut raw_values = Vec::with_capacity(count * T::SIZE);
+        for value in &self.values {
+            value.encode(&mut raw_values);
+        }
+        writer.write_bytes(&raw_values)?;
+
+        Ok(())
+    }
+}
+
+/// Reads `count` values, a chunk of keyframes at a time instead of going through the reader once per value.
+///
+/// Chunks are at most [`MAX_PREALLOCATION`] values, so a bogus count fails once the data runs out
+/// instead of allocating everything up front.
+fn read_values<R: Read, T: CurveValue>(
+    reader: &mut BinaryReader<R>,
+    count: usize,
+) -> Result<Vec<T>, ReplayError> {
+    let mut values = preallocate(count);
+    while values.len() < count {
+        let chunk = (count - values.len()).min(MAX_PREALLOCATION);
+        for raw in reader.read_bytes(chunk * T::SIZE)?.chunks_exact(T::SIZE) {
+            values.push(T::decode(raw)?);
+        }
+    }
+
+    Ok(values)
+}
+
+/// Samples a [`RewindCurve`] while remembering the last keyframe it landed on.
+///
+/// Sequential playback only ever moves a keyframe or two per sample,
+/// so the cursor walks from there instead of binary searching the whole curve again.
+#[derive(Debug, Clone)]
+pub struct CurveCursor<'a, T: Clone> {
+    curve: &'a RewindCurve<T>,
+    interpolated: bool,
+    last_index: Option<usize>,
+}
+
+impl<'a, T: CurveValue> CurveCursor<'a, T> {
+    /// The amount of keyframes walked before falling back to a binary search.
+    const MAX_WALK: usize = 8;
+
+    pub(crate) fn new(curve: &'a RewindCurve<T>, interpolated: bool) -> Self {
+        Self {
+            curve,
+            interpolated,
+            last_index: None,
+        }
+    }
+
+    /// The curve this cursor samples.
+    pub fn curve(&self) -> &'a RewindCurve<T> {
+        self.curve
+    }
+
+    /// Samples the curve at `time`, the same as [`RewindCurve::sample`].
+    pub fn sample(&mut self, time: f32) -> Option<T> {
+        self.sample_with(time, Extrapolation::Clamp)
+    }
+
+    /// Samples the curve at `time`, the same as [`RewindCurve::sample_with`].
+    pub fn sample_with(&mut self, time: f32, extrapolation: Extrapolation) -> Option<T> {
+        self.curve
+            .sample_near(time, self.interpolated, extrapolation, |_| {
+                self.keyframe_index(time)
+            })
+    }
+
+    /// Returns the index of the last keyframe at or before `time`, starting from the previous one.
+    pub fn keyframe_index(&mut self, time: f32) -> Option<usize> {
+        let times = self.curve.times();
+        let count = times.len();
+
+        if let Some(mut idx) = self.last_index.filter(|&idx| idx < count) {
+            for _ in 0..Self::MAX_WALK {
+                if times[idx] > time {
+                    if idx == 0 {
+                        break;
+                    }
+                    idx -= 1;
+                } else if idx + 1 < count && times[idx + 1] <= time {
+                    idx += 1;
+                } else {
+                    self.last_index = Some(idx);
+                    return Some(idx);
+                }
+            }
+        }
+
+        self.last_index = self.curve.keyframe_index(time);
+        self.last_index
+    }
+
+    /// Forgets the last keyframe, the next sample will binary search again.
+    pub fn reset(&mut self) {
+        self.last_index = None;
+    }
 }
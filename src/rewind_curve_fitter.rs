@@ -1,8 +1,16 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    io::{Read, Write},
+};
 
-use csharp_binary_encoding::BinaryReader;
+use csharp_binary_encoding::{BinaryReader, BinaryWriter};
 
-use crate::{ReplayError, rewind_curve::RewindCurve, rewind_curve_type::RewindCurveType};
+use crate::{
+    CurveValue, ParseOptions, ReplayError,
+    parse_options::{Limit, preallocate},
+    rewind_curve::{CurveCursor, Extrapolation, RewindCurve},
+    rewind_curve_type::RewindCurveType,
+};
 
 #[derive(Debug, Clone)]
 pub struct RewindCurveFitter<T: Clone> {
@@ -14,12 +22,30 @@ pub struct RewindCurveFitter<T: Clone> {
     // pub value: Option<T>,
 }
 
-impl<T: Clone> RewindCurveFitter<T> {
-    pub(crate) fn read_from(
-        reader: &mut BinaryReader<&[u8]>,
+impl<T: CurveValue> RewindCurveFitter<T> {
+    /// Samples the curve at `time`, see [`RewindCurve::sample`].  
+    /// Values are only interpolated when [`interpolated`](Self::interpolated) is set, otherwise the previous keyframe is held.
+    pub fn sample(&self, time: f32) -> Option<T> {
+        self.sample_with(time, Extrapolation::Clamp)
+    }
+
+    /// Samples the curve at `time` with the given [`Extrapolation`] at the ends.
+    pub fn sample_with(&self, time: f32, extrapolation: Extrapolation) -> Option<T> {
+        self.recent_curve
+            .sample_at(time, self.interpolated, extrapolation)
+    }
+
+    /// Returns a [`CurveCursor`] that samples the same way as [`sample`](Self::sample).
+    pub fn cursor(&self) -> CurveCursor<'_, T> {
+        CurveCursor::new(&self.recent_curve, self.interpolated)
+    }
+
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
         curve_type: RewindCurveType,
+        options: &ParseOptions,
     ) -> Result<Self, ReplayError> {
-        let _type = unsafe { RewindCurveType::from_i32(reader.read_i32()?) };
+        let _type = RewindCurveType::read_type(reader)?;
         if _type != curve_type {
             return Err(ReplayError::MismatchedCurveTypes {
                 lhs: _type,
@@ -28,13 +54,23 @@ impl<T: Clone> RewindCurveFitter<T> {
         };
 
         let interpolated = reader.read_boolean()?;
-        let recent_curve = unsafe { RewindCurve::<T>::read_from(reader, _type)? };
+        let recent_curve = RewindCurve::<T>::read_from(reader, options)?;
 
         Ok(Self {
             interpolated,
             recent_curve,
         })
     }
+
+    pub(crate) fn write_to<W: Write>(
+        &self,
+        writer: &mut BinaryWriter<W>,
+        curve_type: RewindCurveType,
+    ) -> Result<(), ReplayError> {
+        writer.write_i32(curve_type as i32)?;
+        writer.write_boolean(self.interpolated)?;
+        self.recent_curve.write_to(writer)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,12 +83,13 @@ pub struct RewindCurveFitterArray<T: Clone> {
     pub curves: Vec<RewindCurveFitter<T>>,
 }
 
-impl<T: Clone> RewindCurveFitterArray<T> {
-    pub(crate) fn read_from(
-        reader: &mut BinaryReader<&[u8]>,
+impl<T: CurveValue> RewindCurveFitterArray<T> {
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
         curve_type: RewindCurveType,
+        options: &ParseOptions,
     ) -> Result<Self, ReplayError> {
-        let _type = unsafe { RewindCurveType::from_i32(reader.read_i32()?) };
+        let _type = RewindCurveType::read_type(reader)?;
         if _type != curve_type {
             return Err(ReplayError::MismatchedCurveTypes {
                 lhs: _type,
@@ -61,20 +98,38 @@ impl<T: Clone> RewindCurveFitterArray<T> {
         };
 
         let interpolated = reader.read_boolean()?;
-        let count = reader.read_i32()?;
-        let mut curves: Vec<RewindCurveFitter<T>> = Vec::with_capacity(count as usize);
+        let count = options.read_count(reader, Limit::ArrayLength)?;
+        let mut curves: Vec<RewindCurveFitter<T>> = preallocate(count);
         for _ in 0..count {
-            let mut fitter =
-                RewindCurveFitter::<T>::read_from(reader, _type.get_curve_type_for_array_type())?;
+            let mut fitter = RewindCurveFitter::<T>::read_from(
+                reader,
+                _type.get_curve_type_for_array_type(),
+                options,
+            )?;
             fitter.interpolated = interpolated;
             curves.push(fitter);
         }
 
         Ok(Self {
             interpolated,
-            count,
-            _type: _type,
+            count: count as i32,
+            _type,
             curves,
         })
     }
+
+    pub(crate) fn write_to<W: Write>(
+        &self,
+        writer: &mut BinaryWriter<W>,
+        curve_type: RewindCurveType,
+    ) -> Result<(), ReplayError> {
+        writer.write_i32(curve_type as i32)?;
+        writer.write_boolean(self.interpolated)?;
+        writer.write_i32(self.curves.len() as i32)?;
+        for fitter in &self.curves {
+            fitter.write_to(writer, curve_type.get_curve_type_for_array_type())?;
+        }
+
+        Ok(())
+    }
 }
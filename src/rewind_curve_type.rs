@@ -1,8 +1,10 @@
+use std::io::Read;
+
 use csharp_binary_encoding::BinaryReader;
 
 use crate::{
-    ReplayError, RewindCurveFitter, RewindCurveFitterArray, quaternion::Quaternion,
-    rewind_curve::IRewindCurve, vector2::Vector2, vector3::Vector3,
+    CurveValue, ParseOptions, ReplayError, RewindCurveFitter, RewindCurveFitterArray,
+    quaternion::Quaternion, rewind_curve::IRewindCurve, vector2::Vector2, vector3::Vector3,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -23,26 +25,36 @@ pub enum RewindCurveType {
 }
 
 impl RewindCurveType {
-    /// Creates a [`RewindCurveType`] from a [`i32`]
-    pub unsafe fn from_i32(num: i32) -> Self {
-        unsafe { std::mem::transmute(num) }
+    /// Reads a [`RewindCurveType`] and validates that it is a known, readable type.  
+    pub(crate) fn read_type<R: Read>(reader: &mut BinaryReader<R>) -> Result<Self, ReplayError> {
+        let value = reader.read_i32()?;
+        let offset = reader.num_bytes_read() - 4;
+
+        match Self::try_from(value) {
+            Ok(Self::Unknown) | Err(_) => Err(ReplayError::UnknownCurveType {
+                value,
+                offset: Some(offset),
+            }),
+            Ok(curve_type) => Ok(curve_type),
+        }
     }
 
     /// Returns the matching byte size of the type that the [`RewindCurveType`] corresponds to.  
+    ///
+    /// [`Unknown`](Self::Unknown) has no matching type and returns `0`.  
     pub fn size(&self) -> usize {
-        use std::mem::size_of;
         match self {
-            Self::Float => size_of::<f32>(),
-            Self::Int => size_of::<i32>(),
-            Self::Bool => size_of::<bool>(),
-            Self::Vector2 => size_of::<Vector2>(),
-            Self::Vector3 => size_of::<Vector3>(),
-            Self::Quaternion => size_of::<Quaternion>(),
-            Self::UShort => size_of::<u16>(),
-            Self::UInt32 => size_of::<u32>(),
-            Self::UInt32Array => size_of::<u32>(),
-            Self::Int32Array => size_of::<i32>(),
-            _ => unimplemented!(),
+            Self::Float => f32::SIZE,
+            Self::Int => i32::SIZE,
+            Self::Bool => bool::SIZE,
+            Self::Vector2 => Vector2::SIZE,
+            Self::Vector3 => Vector3::SIZE,
+            Self::Quaternion => Quaternion::SIZE,
+            Self::UShort => u16::SIZE,
+            Self::UInt32 => u32::SIZE,
+            Self::UInt32Array => u32::SIZE,
+            Self::Int32Array => i32::SIZE,
+            Self::Unknown => 0,
         }
     }
 
@@ -55,41 +67,73 @@ impl RewindCurveType {
         }
     }
 
-    pub(crate) fn read_from(
+    pub(crate) fn read_from<R: Read>(
         &self,
-        reader: &mut BinaryReader<&[u8]>,
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
     ) -> Result<IRewindCurve, ReplayError> {
         Ok(match self {
-            RewindCurveType::Unknown => unimplemented!(),
+            RewindCurveType::Unknown => {
+                return Err(ReplayError::UnknownCurveType {
+                    value: *self as i32,
+                    offset: Some(reader.num_bytes_read()),
+                });
+            }
             RewindCurveType::Float => {
-                IRewindCurve::Float(RewindCurveFitter::<f32>::read_from(reader, *self)?)
+                IRewindCurve::Float(RewindCurveFitter::<f32>::read_from(reader, *self, options)?)
             }
             RewindCurveType::Int => {
-                IRewindCurve::Int(RewindCurveFitter::<i32>::read_from(reader, *self)?)
-            }
-            RewindCurveType::Bool => {
-                IRewindCurve::Bool(RewindCurveFitter::<bool>::read_from(reader, *self)?)
-            }
-            RewindCurveType::Vector2 => {
-                IRewindCurve::Vector2(RewindCurveFitter::<Vector2>::read_from(reader, *self)?)
-            }
-            RewindCurveType::Vector3 => {
-                IRewindCurve::Vector3(RewindCurveFitter::<Vector3>::read_from(reader, *self)?)
-            }
-            RewindCurveType::Quaternion => {
-                IRewindCurve::Quaternion(RewindCurveFitter::<Quaternion>::read_from(reader, *self)?)
+                IRewindCurve::Int(RewindCurveFitter::<i32>::read_from(reader, *self, options)?)
             }
+            RewindCurveType::Bool => IRewindCurve::Bool(RewindCurveFitter::<bool>::read_from(
+                reader, *self, options,
+            )?),
+            RewindCurveType::Vector2 => IRewindCurve::Vector2(
+                RewindCurveFitter::<Vector2>::read_from(reader, *self, options)?,
+            ),
+            RewindCurveType::Vector3 => IRewindCurve::Vector3(
+                RewindCurveFitter::<Vector3>::read_from(reader, *self, options)?,
+            ),
+            RewindCurveType::Quaternion => IRewindCurve::Quaternion(
+                RewindCurveFitter::<Quaternion>::read_from(reader, *self, options)?,
+            ),
             RewindCurveType::UShort => {
-                IRewindCurve::UShort(RewindCurveFitter::<u16>::read_from(reader, *self)?)
+                IRewindCurve::UShort(RewindCurveFitter::<u16>::read_from(reader, *self, options)?)
             }
             RewindCurveType::UInt32 => {
-                IRewindCurve::UInt32(RewindCurveFitter::<u32>::read_from(reader, *self)?)
-            }
-            RewindCurveType::UInt32Array => {
-                IRewindCurve::UInt32Array(RewindCurveFitterArray::<u32>::read_from(reader, *self)?)
+                IRewindCurve::UInt32(RewindCurveFitter::<u32>::read_from(reader, *self, options)?)
             }
-            RewindCurveType::Int32Array => {
-                IRewindCurve::Int32Array(RewindCurveFitterArray::<i32>::read_from(reader, *self)?)
+            RewindCurveType::UInt32Array => IRewindCurve::UInt32Array(
+                RewindCurveFitterArray::<u32>::read_from(reader, *self, options)?,
+            ),
+            RewindCurveType::Int32Array => IRewindCurve::Int32Array(
+                RewindCurveFitterArray::<i32>::read_from(reader, *self, options)?,
+            ),
+        })
+    }
+}
+
+impl TryFrom<i32> for RewindCurveType {
+    type Error = ReplayError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            -1 => Self::Unknown,
+            0 => Self::Float,
+            1 => Self::Int,
+            2 => Self::Bool,
+            3 => Self::Vector2,
+            4 => Self::Vector3,
+            5 => Self::Quaternion,
+            6 => Self::UShort,
+            7 => Self::UInt32,
+            8 => Self::UInt32Array,
+            9 => Self::Int32Array,
+            _ => {
+                return Err(ReplayError::UnknownCurveType {
+                    value,
+                    offset: None,
+                });
             }
         })
     }
@@ -1,6 +1,17 @@
-use csharp_binary_encoding::BinaryReader;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    sync::Arc,
+};
 
-use crate::{ReplayError, RewindCurveType, Vector3, rewind_curve::IRewindCurve};
+use csharp_binary_encoding::{BinaryReader, BinaryWriter};
+
+use crate::{
+    ArrayCurveValue, CurveValue, IRewindCurve, ParseOptions, ReplayError, RewindCurveFitter,
+    RewindCurveFitterArray, RewindCurveType, Vector3,
+    buffer_reader::RewindableHeader,
+    parse_options::{Limit, preallocate},
+};
 
 #[derive(Debug, Clone)]
 pub struct Rewindable {
@@ -8,6 +19,152 @@ pub struct Rewindable {
     pub type_name: String,
     pub ref_pos: Vector3,
     pub data: Vec<RewindableData>,
+    /// Where each field is within `data`, built when decoding, see [`find`](Self::find).
+    pub field_index: FieldIndex,
+}
+
+/// Positions within [`Rewindable::data`] keyed by every field's `text` & `index`, for constant time lookups.
+///
+/// Of any duplicates the first field is kept, the same one a linear search finds.  
+/// A hit is checked against the field it points to, so an index that's empty or stale after editing `data`
+/// is never wrong, lookups just fall back to a linear search until [`Rewindable::reindex`] is called.
+#[derive(Debug, Clone, Default)]
+pub struct FieldIndex {
+    by_text: HashMap<String, usize>,
+    by_index: HashMap<i32, usize>,
+}
+
+impl Rewindable {
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
+    ) -> Result<Self, ReplayError> {
+        let header = RewindableHeader::read_from(reader, options)?;
+        let data = RewindableData::read_from(reader, options)
+            .map_err(|e| header.context(e, reader.num_bytes_read()))?;
+
+        let mut rewindable = header.into_rewindable(data);
+        rewindable.share_timelines();
+
+        Ok(rewindable)
+    }
+
+    /// Finds the field called `name` & returns its curve as a [`RewindCurveFitter<T>`].
+    ///
+    /// Fails with [`ReplayError::MissingField`] if there's no such field,
+    /// or [`ReplayError::MismatchedCurveTypes`] if it doesn't hold `T`.
+    pub fn field<T: CurveValue>(&self, name: &str) -> Result<&RewindCurveFitter<T>, ReplayError> {
+        let data = self.data(name)?;
+        T::fitter(&data.curve).ok_or(ReplayError::MismatchedCurveTypes {
+            lhs: T::CURVE_TYPE,
+            rhs: data.rewind_type,
+        })
+    }
+
+    /// Finds the field called `name` & returns its curve as a [`RewindCurveFitterArray<T>`].
+    ///
+    /// Only `i32` & `u32` have arrays, see [`ArrayCurveValue`].
+    pub fn array_field<T: ArrayCurveValue>(
+        &self,
+        name: &str,
+    ) -> Result<&RewindCurveFitterArray<T>, ReplayError> {
+        let data = self.data(name)?;
+        T::fitter_array(&data.curve).ok_or(ReplayError::MismatchedCurveTypes {
+            lhs: T::ARRAY_CURVE_TYPE,
+            rhs: data.rewind_type,
+        })
+    }
+
+    fn data(&self, name: &str) -> Result<&RewindableData, ReplayError> {
+        self.find(name)
+            .ok_or_else(|| ReplayError::MissingField(name.to_owned()))
+    }
+
+    /// Finds the field with the matching [`text`](RewindableData::text), through the [`field_index`](Self::field_index).
+    pub fn find(&self, text: &str) -> Option<&RewindableData> {
+        self.field_index
+            .position(text)
+            .and_then(|i| self.data.get(i))
+            .filter(|d| d.text == text)
+            .or_else(|| self.data.iter().find(|d| d.text == text))
+    }
+
+    /// Finds the field with the matching [`index`](RewindableData::index), through the [`field_index`](Self::field_index).
+    pub fn find_by_index(&self, index: i32) -> Option<&RewindableData> {
+        self.field_index
+            .position_by_index(index)
+            .and_then(|i| self.data.get(i))
+            .filter(|d| d.index == index)
+            .or_else(|| self.data.iter().find(|d| d.index == index))
+    }
+
+    /// Rebuilds the [`field_index`](Self::field_index), call this after adding, removing or reordering fields.
+    pub fn reindex(&mut self) {
+        self.field_index = FieldIndex::new(&self.data);
+    }
+
+    /// Makes all curves with identical keyframe times share a single timeline.  
+    ///
+    /// This is done automatically when decoding, but curves that were built or edited by hand can be deduplicated again with this.
+    pub fn share_timelines(&mut self) {
+        let mut timelines: Vec<Arc<[f32]>> = Vec::new();
+        for field in &mut self.data {
+            field.curve.for_each_timeline_mut(|times| {
+                // compare bits, so sharing never changes what gets written back
+                let same = |other: &Arc<[f32]>| {
+                    other.len() == times.len()
+                        && other
+                            .iter()
+                            .zip(times.iter())
+                            .all(|(a, b)| a.to_bits() == b.to_bits())
+                };
+
+                match timelines.iter().find(|t| same(t)) {
+                    Some(shared) => *times = shared.clone(),
+                    None => timelines.push(times.clone()),
+                }
+            });
+        }
+    }
+
+    pub(crate) fn write_to<W: Write>(
+        &self,
+        writer: &mut BinaryWriter<W>,
+    ) -> Result<(), ReplayError> {
+        writer.write_string(&self.game_object_name)?;
+        writer.write_string(&self.type_name)?;
+        writer.write_f32(self.ref_pos.x)?;
+        writer.write_f32(self.ref_pos.y)?;
+        writer.write_f32(self.ref_pos.z)?;
+
+        RewindableData::write_to(&self.data, writer)
+    }
+}
+
+impl FieldIndex {
+    /// Indexes every field of `data` by its position.
+    pub fn new(data: &[RewindableData]) -> Self {
+        let mut field_index = Self {
+            by_text: HashMap::with_capacity(data.len()),
+            by_index: HashMap::with_capacity(data.len()),
+        };
+        for (i, field) in data.iter().enumerate() {
+            field_index.by_text.entry(field.text.clone()).or_insert(i);
+            field_index.by_index.entry(field.index).or_insert(i);
+        }
+
+        field_index
+    }
+
+    /// The position of the field with the matching [`text`](RewindableData::text).
+    pub fn position(&self, text: &str) -> Option<usize> {
+        self.by_text.get(text).copied()
+    }
+
+    /// The position of the field with the matching [`index`](RewindableData::index).
+    pub fn position_by_index(&self, index: i32) -> Option<usize> {
+        self.by_index.get(&index).copied()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -19,26 +176,59 @@ pub struct RewindableData {
 }
 
 impl RewindableData {
-    pub(crate) fn read_from(
-        reader: &mut BinaryReader<&[u8]>,
+    pub(crate) fn read_from<R: Read>(
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
     ) -> Result<Vec<RewindableData>, ReplayError> {
-        let num = reader.read_i32()?;
+        let num = options.read_count(reader, Limit::Fields)?;
 
-        let mut curves = Vec::with_capacity(num as usize);
+        let mut curves = preallocate(num);
         for _ in 0..num {
-            let index = reader.read_i32()?;
-            let text = reader.read_string()?;
-            let _type = unsafe { RewindCurveType::from_i32(reader.read_i32()?) };
-
-            let curve = _type.read_from(reader)?;
-            curves.push(RewindableData {
-                index,
-                text,
-                rewind_type: _type,
-                curve,
-            });
+            curves.push(RewindableData::read_one(reader, options)?);
         }
 
         Ok(curves)
     }
+
+    /// Reads a single field, errors past the `text` have the field attached as context.
+    pub(crate) fn read_one<R: Read>(
+        reader: &mut BinaryReader<R>,
+        options: &ParseOptions,
+    ) -> Result<RewindableData, ReplayError> {
+        let index = reader.read_i32()?;
+        let text = options.read_string(reader)?;
+
+        let mut read_curve = || -> Result<_, ReplayError> {
+            let _type = RewindCurveType::read_type(reader)?;
+            Ok((_type, _type.read_from(reader, options)?))
+        };
+        let (_type, curve) = read_curve().map_err(|e| {
+            e.with_context(reader.num_bytes_read(), |c| c.field = Some(text.clone()))
+        })?;
+
+        Ok(RewindableData {
+            index,
+            text,
+            rewind_type: _type,
+            curve,
+        })
+    }
+
+    /// Writes all `curves`, the [`RewindCurveType`] is taken from the actual curve and not `rewind_type`.
+    pub(crate) fn write_to<W: Write>(
+        curves: &[RewindableData],
+        writer: &mut BinaryWriter<W>,
+    ) -> Result<(), ReplayError> {
+        writer.write_i32(curves.len() as i32)?;
+
+        for data in curves {
+            writer.write_i32(data.index)?;
+            writer.write_string(&data.text)?;
+            writer.write_i32(data.curve.curve_type() as i32)?;
+
+            data.curve.write_to(writer)?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,209 @@
+use crate::{
+    CurveValue, ParseOptions, Replay, ReplayBuffer, ReplayError, RewindCurveType, Rewindable,
+    RewindableData,
+    buffer_reader::{BufferReader, RewindableHeader, read_at, skip},
+    parse_options::{Limit, preallocate},
+};
+
+/// A [`ReplayBuffer`] recovered by [`decode_replay_buffer_lenient`](Replay::decode_replay_buffer_lenient)
+/// together with everything that went wrong while decoding it.
+#[derive(Debug)]
+pub struct SalvagedReplayBuffer {
+    pub buffer: ReplayBuffer,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl SalvagedReplayBuffer {
+    /// Returns `true` if the buffer was decoded without any problems.
+    pub fn is_complete(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// A problem that was worked around while salvaging a `replay_buffer`.
+#[derive(Debug)]
+pub enum Diagnostic {
+    /// The compressed data could not be fully decompressed,
+    /// only the first `decompressed_len` bytes are used.
+    Truncated {
+        decompressed_len: usize,
+        error: ReplayError,
+    },
+    /// A single [`RewindableData`] could not be decoded and was left out of its [`Rewindable`].
+    SkippedField { error: ReplayError },
+    /// Decoding stopped here, the last [`Rewindable`] may be missing some of its fields.
+    Aborted { error: ReplayError },
+}
+
+impl Replay {
+    /// Decodes as much of the raw `replay_buffer` as possible,
+    /// see [`decode_replay_buffer_lenient_with`](Self::decode_replay_buffer_lenient_with).
+    pub fn decode_replay_buffer_lenient(&self) -> Result<SalvagedReplayBuffer, ReplayError> {
+        self.decode_replay_buffer_lenient_with(&ParseOptions::default())
+    }
+
+    /// Decodes as much of the raw `replay_buffer` as possible, for replays that were cut off or are partially corrupt.
+    ///
+    /// Instead of failing, everything decoded before the first unrecoverable error is returned,
+    /// including the fields of the [`Rewindable`] that was being read when it happened.
+    /// Fields that can't be decoded, like ones with an unknown or mismatched curve type, are skipped
+    /// as long as their length can still be worked out from their sample counts.
+    ///
+    /// Only a missing header is returned as an error.
+    pub fn decode_replay_buffer_lenient_with(
+        &self,
+        options: &ParseOptions,
+    ) -> Result<SalvagedReplayBuffer, ReplayError> {
+        let mut diagnostics = Vec::new();
+
+        let (header, data, error) =
+            BufferReader::new(self.data.replay_buffer.as_slice(), options)?.read_to_end();
+        if let Some(error) = error {
+            diagnostics.push(Diagnostic::Truncated {
+                decompressed_len: data.len(),
+                error,
+            });
+        }
+
+        let mut pos = 0;
+        let mut rewindables = Vec::new();
+        match read_at(&data, &mut pos, |reader| {
+            options.read_count(reader, Limit::Rewindables)
+        }) {
+            Ok(rewindable_count) => {
+                for _ in 0..rewindable_count {
+                    match read_rewindable(&data, &mut pos, options, &mut diagnostics) {
+                        Ok(rewindable) => rewindables.push(rewindable),
+                        Err(partial) => {
+                            rewindables.extend(partial.map(|r| *r));
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(error) => diagnostics.push(Diagnostic::Aborted { error }),
+        }
+
+        Ok(SalvagedReplayBuffer {
+            buffer: ReplayBuffer {
+                header,
+                rewindable_count: rewindables.len() as i32,
+                rewindables,
+            },
+            diagnostics,
+        })
+    }
+}
+
+/// Reads a [`Rewindable`] while skipping fields that can't be decoded.
+///
+/// On an unrecoverable error a [`Diagnostic::Aborted`] is pushed and the partially read rewindable is returned, if any.
+fn read_rewindable(
+    data: &[u8],
+    pos: &mut usize,
+    options: &ParseOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Rewindable, Option<Box<Rewindable>>> {
+    let (header, num) = match read_at(data, pos, |reader| {
+        Ok((
+            RewindableHeader::read_from(reader, options)?,
+            options.read_count(reader, Limit::Fields)?,
+        ))
+    }) {
+        Ok(header) => header,
+        Err(error) => {
+            diagnostics.push(Diagnostic::Aborted { error });
+            return Err(None);
+        }
+    };
+    let mut fields = preallocate(num);
+
+    for _ in 0..num {
+        let error = match read_at(data, pos, |reader| {
+            RewindableData::read_one(reader, options)
+        }) {
+            Ok(field) => {
+                fields.push(field);
+                continue;
+            }
+            Err(error) => header.context(error, *pos as u64),
+        };
+
+        // a failed read leaves `pos` at the start of the field
+        if skip_field(data, pos, options).is_some() {
+            diagnostics.push(Diagnostic::SkippedField { error });
+        } else {
+            diagnostics.push(Diagnostic::Aborted { error });
+            let mut rewindable = header.into_rewindable(fields);
+            rewindable.share_timelines();
+            return Err(Some(Box::new(rewindable)));
+        }
+    }
+
+    let mut rewindable = header.into_rewindable(fields);
+    rewindable.share_timelines();
+
+    Ok(rewindable)
+}
+
+/// Skips over a whole field using only its sample counts, without caring whether its curve types are known or match.
+///
+/// Returns `None` if the length can't be worked out, in which case `pos` is left anywhere within the field.
+fn skip_field(data: &[u8], pos: &mut usize, options: &ParseOptions) -> Option<()> {
+    let rewind_type = read_at(data, pos, |reader| {
+        reader.read_i32()?;
+        options.read_string(reader)?;
+        Ok(reader.read_i32()?)
+    })
+    .ok()?;
+    let rewind_type = RewindCurveType::try_from(rewind_type).unwrap_or(RewindCurveType::Unknown);
+
+    match rewind_type {
+        RewindCurveType::UInt32Array | RewindCurveType::Int32Array => {
+            let count = read_at(data, pos, |reader| {
+                reader.read_i32()?;
+                reader.read_byte()?;
+                options.read_count(reader, Limit::ArrayLength)
+            })
+            .ok()?;
+            for _ in 0..count {
+                skip_fitter(
+                    data,
+                    pos,
+                    rewind_type.get_curve_type_for_array_type(),
+                    options,
+                )?;
+            }
+            Some(())
+        }
+        _ => skip_fitter(data, pos, rewind_type, options),
+    }
+}
+
+/// Skips over a [`RewindCurveFitter`](crate::RewindCurveFitter), sized by its own curve type
+/// or by `fallback` if that one isn't known.
+fn skip_fitter(
+    data: &[u8],
+    pos: &mut usize,
+    fallback: RewindCurveType,
+    options: &ParseOptions,
+) -> Option<()> {
+    let (curve_type, count) = read_at(data, pos, |reader| {
+        let curve_type = RewindCurveType::try_from(reader.read_i32()?);
+        reader.read_byte()?;
+        let count = options.read_count(reader, Limit::SamplesPerCurve)?;
+        reader.read_byte()?;
+        Ok((curve_type.unwrap_or(RewindCurveType::Unknown), count))
+    })
+    .ok()?;
+
+    let size = match curve_type.size() {
+        0 => fallback.size(),
+        size => size,
+    };
+    if size == 0 {
+        return None;
+    }
+
+    skip(data, pos, count.checked_mul(f32::SIZE + size)?).ok()
+}
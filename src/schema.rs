@@ -0,0 +1,320 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{IRewindCurve, ReplayBuffer, RewindCurveType, Rewindable, RewindableData};
+
+/// Every `type_name` seen across one or more [`ReplayBuffer`]'s, together with the fields each one has.
+///
+/// Useful for noticing when a game update adds or changes fields, see [`diff`](Self::diff).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplaySchema {
+    /// The amount of buffers added to the schema.
+    pub buffers: usize,
+    pub types: BTreeMap<String, TypeSchema>,
+}
+
+/// The fields of a single `type_name` within a [`ReplaySchema`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeSchema {
+    /// The amount of [`Rewindable`]'s seen with this `type_name`.
+    pub instances: usize,
+    /// Every field keyed by its [`text`](RewindableData::text).
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+/// A single field within a [`TypeSchema`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSchema {
+    /// Every distinct `index` & `rewind_type` the field was seen with, in the order they were first seen.
+    ///
+    /// More than one means the field conflicts between rewindables or replays, see [`is_conflicting`](Self::is_conflicting).
+    pub variants: Vec<FieldVariant>,
+    /// The amount of [`Rewindable`]'s that had this field.
+    pub occurrences: usize,
+    /// Keyframe counts per occurrence, arrays count the keyframes of all their curves.
+    pub samples: SampleCounts,
+}
+
+/// A single `index` & `rewind_type` combination a [`FieldSchema`] was seen with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldVariant {
+    pub index: i32,
+    pub rewind_type: RewindCurveType,
+}
+
+/// The smallest, largest & total amount of keyframes of a [`FieldSchema`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleCounts {
+    pub min: usize,
+    pub max: usize,
+    pub total: usize,
+}
+
+/// A single difference between two [`ReplaySchema`]'s, see [`ReplaySchema::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    AddedType(String),
+    RemovedType(String),
+    AddedField {
+        type_name: String,
+        text: String,
+        variants: Vec<FieldVariant>,
+    },
+    RemovedField {
+        type_name: String,
+        text: String,
+    },
+    /// The field exists in both, but its `index` or `rewind_type` variants differ.
+    ChangedField {
+        type_name: String,
+        text: String,
+        old: Vec<FieldVariant>,
+        new: Vec<FieldVariant>,
+    },
+}
+
+impl ReplayBuffer {
+    /// Builds the [`ReplaySchema`] of this buffer.
+    pub fn schema(&self) -> ReplaySchema {
+        let mut schema = ReplaySchema::default();
+        schema.add_buffer(self);
+        schema
+    }
+}
+
+impl ReplaySchema {
+    /// Adds every [`Rewindable`] of `buffer` to the schema.
+    pub fn add_buffer(&mut self, buffer: &ReplayBuffer) {
+        self.buffers += 1;
+        for rewindable in &buffer.rewindables {
+            self.add_rewindable(rewindable);
+        }
+    }
+
+    /// Adds a single [`Rewindable`] to the schema.
+    pub fn add_rewindable(&mut self, rewindable: &Rewindable) {
+        let schema = self.types.entry(rewindable.type_name.clone()).or_default();
+        schema.instances += 1;
+
+        for data in &rewindable.data {
+            let samples = sample_count(data);
+            let variant = FieldVariant {
+                index: data.index,
+                rewind_type: data.rewind_type,
+            };
+            schema
+                .fields
+                .entry(data.text.clone())
+                .and_modify(|field| {
+                    field.add_variant(variant);
+                    field.occurrences += 1;
+                    field.samples.add(samples);
+                })
+                .or_insert(FieldSchema {
+                    variants: vec![variant],
+                    occurrences: 1,
+                    samples: SampleCounts {
+                        min: samples,
+                        max: samples,
+                        total: samples,
+                    },
+                });
+        }
+    }
+
+    /// Combines `other` into this schema, as if its buffers had been added here.
+    pub fn merge(&mut self, other: &ReplaySchema) {
+        self.buffers += other.buffers;
+        for (type_name, other) in &other.types {
+            let schema = self.types.entry(type_name.clone()).or_default();
+            schema.instances += other.instances;
+
+            for (text, other) in &other.fields {
+                schema
+                    .fields
+                    .entry(text.clone())
+                    .and_modify(|field| {
+                        for variant in &other.variants {
+                            field.add_variant(*variant);
+                        }
+                        field.occurrences += other.occurrences;
+                        field.samples.merge(&other.samples);
+                    })
+                    .or_insert_with(|| other.clone());
+            }
+        }
+    }
+
+    /// Lists everything that is different in `new` compared to this schema.
+    ///
+    /// Only types & fields are compared, instance & sample counts are expected to differ between replays.  
+    /// A field is changed if the set of its [`variants`](FieldSchema::variants) differs,
+    /// so a conflict that only exists in one of the schemas is reported too.
+    pub fn diff(&self, new: &ReplaySchema) -> Vec<SchemaChange> {
+        let mut changes = Vec::new();
+
+        for (type_name, old) in &self.types {
+            let Some(new) = new.types.get(type_name) else {
+                changes.push(SchemaChange::RemovedType(type_name.clone()));
+                continue;
+            };
+
+            for (text, old) in &old.fields {
+                match new.fields.get(text) {
+                    None => changes.push(SchemaChange::RemovedField {
+                        type_name: type_name.clone(),
+                        text: text.clone(),
+                    }),
+                    Some(new) if !old.same_variants(new) => {
+                        changes.push(SchemaChange::ChangedField {
+                            type_name: type_name.clone(),
+                            text: text.clone(),
+                            old: old.variants.clone(),
+                            new: new.variants.clone(),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            for (text, new) in &new.fields {
+                if !old.fields.contains_key(text) {
+                    changes.push(SchemaChange::AddedField {
+                        type_name: type_name.clone(),
+                        text: text.clone(),
+                        variants: new.variants.clone(),
+                    });
+                }
+            }
+        }
+
+        for type_name in new.types.keys() {
+            if !self.types.contains_key(type_name) {
+                changes.push(SchemaChange::AddedType(type_name.clone()));
+            }
+        }
+
+        changes
+    }
+}
+
+impl FieldSchema {
+    /// The `index` the field was first seen with.
+    pub fn index(&self) -> i32 {
+        self.variants[0].index
+    }
+
+    /// The `rewind_type` the field was first seen with.
+    pub fn rewind_type(&self) -> RewindCurveType {
+        self.variants[0].rewind_type
+    }
+
+    /// Returns `true` if the field was seen with more than one `index` or `rewind_type`.
+    pub fn is_conflicting(&self) -> bool {
+        self.variants.len() > 1
+    }
+
+    fn add_variant(&mut self, variant: FieldVariant) {
+        if !self.variants.contains(&variant) {
+            self.variants.push(variant);
+        }
+    }
+
+    fn same_variants(&self, other: &FieldSchema) -> bool {
+        self.variants.len() == other.variants.len()
+            && self.variants.iter().all(|v| other.variants.contains(v))
+    }
+}
+
+impl SampleCounts {
+    fn add(&mut self, samples: usize) {
+        self.min = self.min.min(samples);
+        self.max = self.max.max(samples);
+        self.total += samples;
+    }
+
+    fn merge(&mut self, other: &SampleCounts) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.total += other.total;
+    }
+}
+
+fn sample_count(data: &RewindableData) -> usize {
+    match &data.curve {
+        IRewindCurve::Float(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::Int(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::Bool(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::Vector2(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::Vector3(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::Quaternion(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::UShort(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::UInt32(fitter) => fitter.recent_curve.len(),
+        IRewindCurve::UInt32Array(array) => array.curves.iter().map(|f| f.recent_curve.len()).sum(),
+        IRewindCurve::Int32Array(array) => array.curves.iter().map(|f| f.recent_curve.len()).sum(),
+    }
+}
+
+/// Dumps the schema as one line per type & field, fields ordered by their `index`.
+impl Display for ReplaySchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (type_name, schema) in &self.types {
+            writeln!(f, "{type_name} (x{})", schema.instances)?;
+
+            let mut fields = schema.fields.iter().collect::<Vec<_>>();
+            fields.sort_by_key(|(_, field)| field.index());
+            for (text, field) in fields {
+                writeln!(
+                    f,
+                    "  {text}: {} ({}..={} samples)",
+                    Variants(&field.variants),
+                    field.samples.min,
+                    field.samples.max
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddedType(type_name) => write!(f, "+ {type_name}"),
+            Self::RemovedType(type_name) => write!(f, "- {type_name}"),
+            Self::AddedField {
+                type_name,
+                text,
+                variants,
+            } => write!(f, "+ {type_name}.{text}: {}", Variants(variants)),
+            Self::RemovedField { type_name, text } => write!(f, "- {type_name}.{text}"),
+            Self::ChangedField {
+                type_name,
+                text,
+                old,
+                new,
+            } => write!(
+                f,
+                "~ {type_name}.{text}: {} -> {}",
+                Variants(old),
+                Variants(new)
+            ),
+        }
+    }
+}
+
+/// Formats variants like `[0] Float | [1] Int`.
+struct Variants<'a>(&'a [FieldVariant]);
+
+impl Display for Variants<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, variant) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "[{}] {:?}", variant.index, variant.rewind_type)?;
+        }
+
+        Ok(())
+    }
+}